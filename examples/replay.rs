@@ -0,0 +1,37 @@
+//! Replay a recording written by the GUI, with no Simulation Controller attached.
+//!
+//! ```sh
+//! cargo run --example replay -- recording.jsonl
+//! ```
+
+use eframe::egui;
+
+use gui::{
+    app::SimCtrlGUI,
+    constants::{HEIGHT, WIDTH},
+};
+
+fn main() -> eframe::Result {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "recording.jsonl".to_string());
+
+    let app = match SimCtrlGUI::replay(&path) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([WIDTH, HEIGHT]),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "Simulation Controller - Replay",
+        options,
+        Box::new(|_cc| Ok(Box::new(app))),
+    )
+}
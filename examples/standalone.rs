@@ -0,0 +1,61 @@
+//! Run the GUI against the built-in mock Simulation Controller.
+//!
+//! ```sh
+//! cargo run --example standalone -- examples/network.toml
+//! ```
+//!
+//! With `--seed <seed>` the layout and the synthetic traffic are the same on every run.
+
+use eframe::egui;
+
+use gui::{
+    app::SimCtrlGUI,
+    constants::{HEIGHT, WIDTH},
+    mock::{MockController, MockSettings},
+};
+
+fn main() -> eframe::Result {
+    let mut path = "examples/network.toml".to_string();
+    let mut seed = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            let Some(Ok(value)) = args.next().map(|value| value.parse::<u64>()) else {
+                eprintln!("--seed expects a non-negative integer");
+                std::process::exit(1);
+            };
+            seed = Some(value);
+        } else {
+            path = arg;
+        }
+    }
+
+    let config = match MockController::load_config(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    let settings = MockSettings {
+        seed,
+        ..MockSettings::default()
+    };
+    let (sender, receiver, _controller) = MockController::spawn(config, settings);
+    let mut app = SimCtrlGUI::new(sender, receiver);
+    if let Some(seed) = seed {
+        app = app.with_layout_seed(seed);
+    }
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([WIDTH, HEIGHT]),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "Simulation Controller",
+        options,
+        Box::new(|_cc| Ok(Box::new(app))),
+    )
+}
@@ -1,18 +1,19 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::path::Path;
+
 use colored::Colorize;
 use eframe::egui;
 use log::warn;
 
 use crate::{
-    logic::{handlers::EventHandler, state::GUIState},
+    logic::{error::GuiError, handlers::IngestionConfig, GuiCore},
     ui::MainUI,
 };
 
-/// Main GUI application struct
+/// Main GUI application struct, a thin view on top of [`GuiCore`]
 pub struct SimCtrlGUI {
-    pub state: GUIState,
-    event_handler: EventHandler,
+    pub core: GuiCore,
     main_ui: MainUI,
 }
 
@@ -22,29 +23,56 @@ impl SimCtrlGUI {
         sender: crossbeam_channel::Sender<messages::gui_commands::GUICommands>,
         receiver: crossbeam_channel::Receiver<messages::gui_commands::GUIEvents>,
     ) -> Self {
-        let state = GUIState::new(sender, receiver);
+        Self::with_ingestion_config(sender, receiver, IngestionConfig::default())
+    }
+
+    /// Create the GUI with custom limits for per-frame event ingestion
+    #[must_use]
+    pub fn with_ingestion_config(
+        sender: crossbeam_channel::Sender<messages::gui_commands::GUICommands>,
+        receiver: crossbeam_channel::Receiver<messages::gui_commands::GUIEvents>,
+        config: IngestionConfig,
+    ) -> Self {
+        Self::from_core(GuiCore::with_ingestion_config(sender, receiver, config))
+    }
+
+    /// Seed the force-directed layout from start-up, see [`GuiCore::with_layout_seed`]
+    #[must_use]
+    pub fn with_layout_seed(mut self, seed: u64) -> Self {
+        self.core = self.core.with_layout_seed(seed);
+        self
+    }
 
+    /// Create a GUI replaying a recording, with no Simulation Controller attached
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the recording cannot be loaded.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, GuiError> {
+        GuiCore::replay(path).map(Self::from_core)
+    }
+
+    /// Wrap an existing core in the GUI
+    #[must_use]
+    pub fn from_core(core: GuiCore) -> Self {
         Self {
-            event_handler: EventHandler::new(),
+            core,
             main_ui: MainUI::new(),
-            state,
         }
     }
 }
 
 impl eframe::App for SimCtrlGUI {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        if self.state.initialized {
-            // Handle incoming events
-            self.event_handler.handle_events(&mut self.state, ctx);
+        if !self.core.is_initialized() {
+            warn!("[ {} ] Waiting for initialization", "GUI".green());
+        }
 
+        // Handle incoming events (only the topology until initialized)
+        self.core.pump();
+
+        if self.core.is_initialized() {
             // Render main UI
-            self.main_ui.render(&mut self.state, ctx);
-        } else {
-            warn!("[ {} ] Waiting for initialization", "GUI".green());
-            // Handle initialization
-            self.event_handler
-                .handle_initialization(&mut self.state, ctx);
+            self.main_ui.render(&mut self.core, ctx);
         }
 
         // Request continuous repainting for animations
@@ -1,11 +1,17 @@
 use eframe::egui::Color32;
 
-/// Main window height
+/// Initial main window height, the network canvas follows the actual window size
 pub const HEIGHT: f32 = 900.0;
 
-/// Main window width  
+/// Initial main window width, the network canvas follows the actual window size
 pub const WIDTH: f32 = 900.0;
 
+/// Distance kept between laid out nodes and the edges of the canvas
+pub const LAYOUT_MARGIN: f32 = 25.0;
+
+/// Duration of the animated move of the nodes to a new layout
+pub const LAYOUT_TRANSITION_SECS: f32 = 0.8;
+
 /// Node visualization radius
 pub const NODE_RADIUS: f32 = 10.0;
 
@@ -17,10 +23,71 @@ pub const MEDIA_CONTENT_SERVER_COLOR: Color32 = Color32::RED;
 pub const CHAT_CLIENT_COLOR: Color32 = Color32::YELLOW;
 pub const MEDIA_CLIENT_COLOR: Color32 = Color32::ORANGE;
 
+/// Link color for visualization
+pub const EDGE_COLOR: Color32 = Color32::GRAY;
+
+/// Outline of the nodes selected for bulk actions, and of the selection rectangle
+pub const SELECTION_COLOR: Color32 = Color32::from_rgb(0, 200, 255);
+
+/// Camera zoom limits of the network canvas
+pub const MIN_ZOOM: f32 = 0.1;
+pub const MAX_ZOOM: f32 = 10.0;
+
 /// UI spacing and positioning
-pub const LEGEND_Y_POS: f32 = 40.0;
 pub const LEGEND_X_START: f32 = 10.0;
 pub const LEGEND_SPACING: f32 = 5.0;
 
 /// Animation timing
-pub const PACKET_ANIMATION_DURATION_SECS: f32 = 0.005;
+pub const PACKET_ANIMATION_DURATION_SECS: f32 = 0.5;
+pub const MAX_PACKET_MARKERS: usize = 500;
+pub const PACKET_MARKER_RADIUS: f32 = 4.0;
+
+/// Maximum number of session routes kept for tracing
+pub const MAX_ROUTE_TRACES: usize = 200;
+
+/// Maximum number of floods kept, and the time the wave of a flood takes to fade at a node
+pub const MAX_FLOOD_TRACES: usize = 50;
+pub const FLOOD_WAVE_SECS: f32 = 1.0;
+
+/// Time a spawned drone stays highlighted
+pub const SPAWN_HIGHLIGHT_SECS: f32 = 3.0;
+
+/// Traffic heatmap: time over which packets fade out of the link rates
+pub const TRAFFIC_WINDOW_SECS: f32 = 5.0;
+pub const HEATMAP_MAX_WIDTH: f32 = 8.0;
+
+/// Seconds of per-node traffic kept for rates and sparklines
+pub const NODE_TRAFFIC_WINDOW_SECS: usize = 30;
+
+/// PDR check: z-score of the confidence interval (95%) and fragments needed for a verdict
+pub const PDR_CHECK_Z: f32 = 1.96;
+pub const PDR_CHECK_MIN_SAMPLES: u64 = 30;
+/// PDR check: consecutive windows of `PDR_CHECK_MIN_SAMPLES` fragments that must agree
+/// before the verdict flips
+pub const PDR_CHECK_WINDOWS: u32 = 3;
+
+/// Seconds of network metrics kept for the dashboard
+pub const METRICS_HISTORY_SECS: usize = 600;
+
+/// Event ingestion limits
+pub const MAX_EVENTS_PER_FRAME: usize = 512;
+pub const MAX_EVENT_PROCESSING_MS: u64 = 8;
+pub const EVENT_COALESCE_THRESHOLD: usize = 2048;
+
+/// Maximum number of entries kept in the error panel
+pub const MAX_ERROR_RECORDS: usize = 100;
+
+/// Maximum number of topology edits that can be undone
+pub const MAX_EDIT_HISTORY: usize = 100;
+
+/// Maximum number of lines kept in the console output, and of inputs kept in its history
+pub const MAX_CONSOLE_OUTPUT: usize = 500;
+pub const MAX_CONSOLE_HISTORY: usize = 100;
+
+/// Time the Simulation Controller has to confirm a command before it is flagged as failed
+pub const COMMAND_TIMEOUT_MS: u64 = 5000;
+
+/// Mock Simulation Controller
+pub const MOCK_TRAFFIC_INTERVAL_MS: u64 = 200;
+pub const MOCK_FLOOD_EVERY: u32 = 25;
+pub const MOCK_MAX_RETRIES: usize = 3;
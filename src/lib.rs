@@ -1,4 +1,5 @@
 pub mod app;
 pub mod constants;
 pub mod logic;
+pub mod mock;
 pub mod ui;
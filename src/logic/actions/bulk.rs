@@ -0,0 +1,122 @@
+use colored::Colorize;
+use log::info;
+
+use wg_2024::{network::NodeId, packet::NodeType};
+
+use crate::logic::{
+    actions::{crash, remove_sender, set_pdr},
+    error::GuiError,
+    state::GUIState,
+};
+
+/// The selected nodes that are drones, in increasing id order
+#[must_use]
+pub fn selected_drones(state: &GUIState) -> Vec<NodeId> {
+    state
+        .selection
+        .iter()
+        .filter(|id| {
+            state
+                .nodes
+                .get(id)
+                .is_some_and(|node| node.node_type == NodeType::Drone)
+        })
+        .collect()
+}
+
+/// Run `apply` on every item in order, recording the commands sent as a single edit
+///
+/// Stops at the first failure: the commands already sent stay applied and grouped, so a
+/// single undo still reverts them, and the error tells how many items were handled.
+fn apply_grouped<T>(
+    state: &mut GUIState,
+    action: &str,
+    items: Vec<T>,
+    mut apply: impl FnMut(&mut GUIState, T) -> Result<(), GuiError>,
+) -> Result<(), GuiError> {
+    let total = items.len();
+    state.history.begin_group();
+    let mut result = Ok(());
+    for (done, item) in items.into_iter().enumerate() {
+        if let Err(error) = apply(state, item) {
+            result = Err(GuiError::Incomplete {
+                action: action.to_string(),
+                done,
+                total,
+                error: Box::new(error),
+            });
+            break;
+        }
+    }
+    state.history.end_group();
+    result
+}
+
+/// Crash every selected drone, recorded as a single edit
+///
+/// # Errors
+/// Returns [`GuiError::Incomplete`] with the error of the first drone that cannot be
+/// crashed. The drones before it are crashed and recorded as a single edit, the following
+/// ones are left untouched.
+pub fn crash_selected(state: &mut GUIState) -> Result<(), GuiError> {
+    let drones = selected_drones(state);
+    info!(
+        "[ {} ] Crashing the selected drones {:?}",
+        "GUI".green(),
+        drones
+    );
+
+    apply_grouped(state, "Crashing the selected drones", drones, crash)
+}
+
+/// Set the same packet drop rate on every selected drone, recorded as a single edit
+///
+/// # Errors
+/// Returns [`GuiError::InvalidInput`] if `pdr` is not within `0.0..=1.0`, or
+/// [`GuiError::Incomplete`] with the error of the first drone whose PDR cannot be set. The
+/// drones before it keep the new PDR, recorded as a single edit, the following ones are
+/// left untouched.
+pub fn set_pdr_selected(state: &mut GUIState, pdr: f32) -> Result<(), GuiError> {
+    if !(0.0..=1.0).contains(&pdr) {
+        return Err(GuiError::InvalidInput(
+            "The PDR value must be between 0.0 and 1.0".to_string(),
+        ));
+    }
+
+    let drones = selected_drones(state);
+    info!(
+        "[ {} ] Setting PDR {} on the selected drones {:?}",
+        "GUI".green(),
+        pdr,
+        drones
+    );
+
+    apply_grouped(
+        state,
+        &format!("Setting PDR {pdr} on the selected drones"),
+        drones,
+        |state, drone| set_pdr(state, drone, pdr),
+    )
+}
+
+/// Remove every link between the two groups of the selection, recorded as a single edit
+///
+/// # Errors
+/// Returns [`GuiError::Incomplete`] with the error of the first link that cannot be removed.
+/// The links before it are removed and recorded as a single edit, the following ones are
+/// left untouched.
+pub fn remove_links_between_groups(state: &mut GUIState) -> Result<(), GuiError> {
+    let links = state.selection.links_between_groups(&state.graph);
+    info!(
+        "[ {} ] Removing the links between the selected groups {:?}",
+        "GUI".green(),
+        links
+    );
+
+    apply_grouped(
+        state,
+        "Removing the links between the selected groups",
+        links,
+        |state, (a, b)| remove_sender(state, a, b),
+    )
+}
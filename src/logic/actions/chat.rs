@@ -1,84 +1,101 @@
 use colored::Colorize;
-use log::{error, info};
+use log::info;
 
 use wg_2024::network::NodeId;
 
 use messages::gui_commands::GUICommands;
 
-use crate::logic::state::GUIState;
+use crate::logic::{error::GuiError, state::GUIState};
 
-pub fn send_message(state: &mut GUIState, src: NodeId, dest: NodeId, msg: &str) {
-    match state.sender.send(GUICommands::SendMessageTo(src, dest, msg.to_string())) {
-        Ok(()) => info!(
-            "[ {} ] Successfully sent GUICommand::SendMessageTo({}, {}, {}) from GUI to Simulation Controller",
-            "GUI".green(),
-            src,
-            dest,
-            msg
-        ),
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::SendMessageTo({}, {}, {}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
+/// Ask a chat client to send `msg` to another client
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn send_message(
+    state: &mut GUIState,
+    src: NodeId,
+    dest: NodeId,
+    msg: &str,
+) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::SendMessageTo(src, dest, msg.to_string())) {
+        Ok(()) => {
+            info!(
+                "[ {} ] Successfully sent GUICommand::SendMessageTo({}, {}, {}) from GUI to Simulation Controller",
+                "GUI".green(),
                 src,
                 dest,
-                msg,
-                e
+                msg
             );
-        },
+            Ok(())
+        }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::SendMessageTo({src}, {dest}, {msg})"),
+            reason: e.to_string(),
+        }),
     }
 }
 
-pub fn register(state: &mut GUIState, client: NodeId, server: NodeId) {
-    match state.sender.send(GUICommands::RegisterTo(client, server)) {
-        Ok(()) => info!(
-            "[ {} ] Successfully sent GUICommand::RegisterTo({}, {}) from GUI to Simulation Controller",
-            "GUI".green(),
-            client,
-            server
-        ),
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::RegisterTo({}, {}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
+/// Ask a chat client to register to a communication server
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn register(state: &mut GUIState, client: NodeId, server: NodeId) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::RegisterTo(client, server)) {
+        Ok(()) => {
+            info!(
+                "[ {} ] Successfully sent GUICommand::RegisterTo({}, {}) from GUI to Simulation Controller",
+                "GUI".green(),
                 client,
-                server,
-                e
+                server
             );
-        },
+            Ok(())
+        }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::RegisterTo({client}, {server})"),
+            reason: e.to_string(),
+        }),
     }
 }
 
-pub fn get_list(state: &mut GUIState, client: NodeId) {
-    match state.sender.send(GUICommands::GetClientList(client)) {
-        Ok(()) => info!(
-            "[ {} ] Successfully sent GUICommand::GetClientList({}) from GUI to Simulation Controller",
-            "GUI".green(),
-            client,
-        ),
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::GetClientList({}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
+/// Ask a chat client for the list of registered clients
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn get_list(state: &mut GUIState, client: NodeId) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::GetClientList(client)) {
+        Ok(()) => {
+            info!(
+                "[ {} ] Successfully sent GUICommand::GetClientList({}) from GUI to Simulation Controller",
+                "GUI".green(),
                 client,
-                e
             );
-        },
+            Ok(())
+        }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::GetClientList({client})"),
+            reason: e.to_string(),
+        }),
     }
 }
 
-pub fn logout(state: &mut GUIState, client: NodeId, server: NodeId) {
-    match state.sender.send(GUICommands::LogOut(client, server)) {
-        Ok(()) => info!(
-            "[ {} ] Successfully sent GUICommand::LogOut({}, {}) from GUI to Simulation Controller",
-            "GUI".green(),
-            client,
-            server
-        ),
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::LogOut({}, {}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
+/// Ask a chat client to log out from a communication server
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn logout(state: &mut GUIState, client: NodeId, server: NodeId) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::LogOut(client, server)) {
+        Ok(()) => {
+            info!(
+                "[ {} ] Successfully sent GUICommand::LogOut({}, {}) from GUI to Simulation Controller",
+                "GUI".green(),
                 client,
-                server,
-                e
+                server
             );
+            Ok(())
         }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::LogOut({client}, {server})"),
+            reason: e.to_string(),
+        }),
     }
 }
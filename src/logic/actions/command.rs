@@ -0,0 +1,30 @@
+use messages::gui_commands::GUICommands;
+
+use crate::logic::{
+    actions::{
+        add_sender, ask_for_file_list, crash, get_file, get_list, logout, register, remove_sender,
+        send_message, set_pdr, spawn,
+    },
+    error::GuiError,
+    state::GUIState,
+};
+
+/// Issue any `GUICommands` through its matching action
+///
+/// # Errors
+/// Returns the [`GuiError`] of the matching action.
+pub fn execute(state: &mut GUIState, command: GUICommands) -> Result<(), GuiError> {
+    match command {
+        GUICommands::Crash(drone) => crash(state, drone),
+        GUICommands::SetPDR(drone, pdr) => set_pdr(state, drone, pdr),
+        GUICommands::Spawn(id, neighbors, pdr) => spawn(state, id, &neighbors, pdr),
+        GUICommands::AddSender(node_id, to_add) => add_sender(state, node_id, to_add),
+        GUICommands::RemoveSender(node_id, to_remove) => remove_sender(state, node_id, to_remove),
+        GUICommands::SendMessageTo(src, dest, msg) => send_message(state, src, dest, &msg),
+        GUICommands::RegisterTo(client, server) => register(state, client, server),
+        GUICommands::GetClientList(client) => get_list(state, client),
+        GUICommands::LogOut(client, server) => logout(state, client, server),
+        GUICommands::AskForFileList(client, server) => ask_for_file_list(state, client, server),
+        GUICommands::GetFile(client, server, title) => get_file(state, client, server, &title),
+    }
+}
@@ -1,61 +1,108 @@
 use colored::Colorize;
-use log::{error, info};
+use log::info;
 
 use wg_2024::network::NodeId;
 
 use messages::gui_commands::GUICommands;
 
-use crate::logic::{nodes::NodeGUI, state::GUIState};
-
-pub fn crash(state: &mut GUIState, drone: NodeId) {
-    match state.sender.send(GUICommands::Crash(drone)) {
-        Ok(()) => info!(
-            "[ {} ] Successfully sent GUICommand::Crash() from GUI to Simulation Controller",
-            "GUI".green(),
-        ),
-        Err(e) => {
-            error!(
-                "[ {} ] Unable to send GUICommand::Crash() from GUI to Simulation Controller: {}",
-                "GUI".red(),
-                e
+use crate::logic::{error::GuiError, state::GUIState};
+
+/// Crash a drone
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn crash(state: &mut GUIState, drone: NodeId) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::Crash(drone)) {
+        Ok(()) => {
+            info!(
+                "[ {} ] Successfully sent GUICommand::Crash({}) from GUI to Simulation Controller",
+                "GUI".green(),
+                drone
             );
+            state.history.record(GUICommands::Crash(drone), None);
+            Ok(())
         }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::Crash({drone})"),
+            reason: e.to_string(),
+        }),
     }
 }
 
-pub fn set_pdr(state: &mut GUIState, instance: &mut NodeGUI, pdr: f32) {
-    match state.sender.send(GUICommands::SetPDR(instance.id, pdr)) {
+/// Change the packet drop rate of a drone
+///
+/// # Errors
+/// Returns [`GuiError::InvalidInput`] if `pdr` is not within `0.0..=1.0`, or
+/// [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn set_pdr(state: &mut GUIState, drone: NodeId, pdr: f32) -> Result<(), GuiError> {
+    if !(0.0..=1.0).contains(&pdr) {
+        return Err(GuiError::InvalidInput(
+            "The PDR value must be between 0.0 and 1.0".to_string(),
+        ));
+    }
+
+    // Undoing restores the PDR the drone had before this change
+    let previous = state.nodes.get(&drone).map(|instance| instance.pdr);
+
+    match state.send_command(GUICommands::SetPDR(drone, pdr)) {
         Ok(()) => {
-            info!("[ {} ] Successfully sent GUICommand::SetPDR({}, {}) from GUI to Simulation Controller", "GUI".green(), instance.id, pdr);
-            instance.pdr = pdr;
-        }
-        Err(e) => {
-            error!(
-                "[ {} ] Unable to send GUICommand::SetPDR from GUI to Simulation Controller: {}",
-                "GUI".red(),
-                e
+            info!("[ {} ] Successfully sent GUICommand::SetPDR({}, {}) from GUI to Simulation Controller", "GUI".green(), drone, pdr);
+            // The Simulation Controller sends no event for a new PDR, so it is committed
+            // as soon as it is sent
+            if let Some(instance) = state.nodes.get_mut(&drone) {
+                instance.pdr = pdr;
+                instance.drone_params.unconfirmed_pdr = None;
+            }
+            state.pdr_checks.restart(drone, pdr);
+            state.history.record(
+                GUICommands::SetPDR(drone, pdr),
+                previous.map(|previous| GUICommands::SetPDR(drone, previous)),
             );
+            Ok(())
         }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::SetPDR({drone}, {pdr})"),
+            reason: e.to_string(),
+        }),
     }
 }
 
-pub fn spawn(state: &mut GUIState, id: NodeId, neighbors: &Vec<NodeId>, pdr: f32) {
-    match state.sender.send(GUICommands::Spawn(id, neighbors.clone(), pdr)) {
-        Ok(()) => info!(
-            "[ {} ] Successfully sent GUICommand::Spawn({}, {:?}, {}) from GUI to Simulation Controller",
-            "GUI".green(),
-            id,
-            neighbors,
-            pdr
-        ),
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::Spawn({}, {:?}, {}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
+/// Spawn a new drone connected to `neighbors`
+///
+/// # Errors
+/// Returns [`GuiError::InvalidInput`] if `id` is already taken, [`GuiError::UnknownNode`]
+/// if a neighbor does not exist, or [`GuiError::CommandSend`] if the Simulation Controller
+/// channel is closed.
+pub fn spawn(
+    state: &mut GUIState,
+    id: NodeId,
+    neighbors: &Vec<NodeId>,
+    pdr: f32,
+) -> Result<(), GuiError> {
+    if state.nodes.contains_key(&id) {
+        return Err(GuiError::InvalidInput(format!("Node {id} already exists")));
+    }
+    if let Some(unknown) = neighbors.iter().find(|n| !state.nodes.contains_key(n)) {
+        return Err(GuiError::UnknownNode(*unknown));
+    }
+
+    match state.send_command(GUICommands::Spawn(id, neighbors.clone(), pdr)) {
+        Ok(()) => {
+            info!(
+                "[ {} ] Successfully sent GUICommand::Spawn({}, {:?}, {}) from GUI to Simulation Controller",
+                "GUI".green(),
                 id,
                 neighbors,
-                pdr,
-                e
+                pdr
             );
-        },
+            state
+                .history
+                .record(GUICommands::Spawn(id, neighbors.clone(), pdr), None);
+            Ok(())
+        }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::Spawn({id}, {neighbors:?}, {pdr})"),
+            reason: e.to_string(),
+        }),
     }
 }
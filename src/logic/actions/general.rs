@@ -1,17 +1,22 @@
 use colored::Colorize;
-use log::{error, info};
+use log::info;
 
 use wg_2024::network::NodeId;
 
 use messages::gui_commands::GUICommands;
 
-use crate::logic::state::GUIState;
+use crate::logic::{error::GuiError, state::GUIState};
 
-pub fn remove_sender(state: &mut GUIState, node_id: NodeId, to_remove: NodeId) {
-    match state
-        .sender
-        .send(GUICommands::RemoveSender(node_id, to_remove))
-    {
+/// Remove the link between two nodes
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn remove_sender(
+    state: &mut GUIState,
+    node_id: NodeId,
+    to_remove: NodeId,
+) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::RemoveSender(node_id, to_remove)) {
         Ok(()) => {
             info!(
                 "[ {} ] Successfully sent GUICommand::RemoveSender({}, {}) from GUI to Simulation Controller",
@@ -19,20 +24,25 @@ pub fn remove_sender(state: &mut GUIState, node_id: NodeId, to_remove: NodeId) {
                 node_id,
                 to_remove
             );
-        }
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::RemoveSender({}, {}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
-                node_id,
-                to_remove,
-                e
+            state.history.record(
+                GUICommands::RemoveSender(node_id, to_remove),
+                Some(GUICommands::AddSender(node_id, to_remove)),
             );
+            Ok(())
         }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::RemoveSender({node_id}, {to_remove})"),
+            reason: e.to_string(),
+        }),
     }
 }
 
-pub fn add_sender(state: &mut GUIState, node_id: NodeId, to_add: NodeId) {
-    match state.sender.send(GUICommands::AddSender(node_id, to_add)) {
+/// Add a link between two nodes
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn add_sender(state: &mut GUIState, node_id: NodeId, to_add: NodeId) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::AddSender(node_id, to_add)) {
         Ok(()) => {
             info!(
                 "[ {} ] Successfully sent GUICommand::AddSender({}, {}) from GUI to Simulation Controller",
@@ -40,14 +50,15 @@ pub fn add_sender(state: &mut GUIState, node_id: NodeId, to_add: NodeId) {
                 node_id,
                 to_add
             );
-        }
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::AddSender({}, {}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
-                node_id,
-                to_add,
-                e
+            state.history.record(
+                GUICommands::AddSender(node_id, to_add),
+                Some(GUICommands::RemoveSender(node_id, to_add)),
             );
+            Ok(())
         }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::AddSender({node_id}, {to_add})"),
+            reason: e.to_string(),
+        }),
     }
 }
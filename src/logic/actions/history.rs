@@ -0,0 +1,70 @@
+use colored::Colorize;
+use log::info;
+
+use messages::gui_commands::GUICommands;
+
+use crate::logic::{actions::execute, error::GuiError, state::GUIState};
+
+/// Revert the latest topology edit by issuing its inverse commands
+///
+/// The latest edits that cannot be reverted are dropped from the history, and the edit
+/// before them is reverted in the same undo.
+///
+/// # Errors
+/// Returns [`GuiError::Irreversible`] if no edit left in the history can be reverted, or
+/// the [`GuiError`] of the action issuing an inverse command.
+pub fn undo(state: &mut GUIState) -> Result<(), GuiError> {
+    // The latest irreversible edit dropped, reported if nothing can be reverted
+    let mut skipped = None;
+    let (edit, inverse) = loop {
+        let Some(edit) = state.history.next_undo().cloned() else {
+            return match skipped {
+                Some(edit) => Err(GuiError::Irreversible(edit)),
+                None => Ok(()),
+            };
+        };
+        if let Some(inverse) = edit.inverse.clone() {
+            break (edit, inverse);
+        }
+
+        info!(
+            "[ {} ] Skipped {}, it cannot be undone",
+            "GUI".green(),
+            edit
+        );
+        state.history.discard_undo();
+        skipped = Some(edit.to_string());
+    };
+
+    restore(state, inverse)?;
+
+    state.history.mark_undone();
+    info!("[ {} ] Undone {}", "GUI".green(), edit);
+    Ok(())
+}
+
+/// Issue again the latest undone topology edit
+///
+/// # Errors
+/// Returns the [`GuiError`] of the action issuing a command.
+pub fn redo(state: &mut GUIState) -> Result<(), GuiError> {
+    let Some(edit) = state.history.next_redo().cloned() else {
+        return Ok(());
+    };
+
+    restore(state, edit.commands.clone())?;
+
+    state.history.mark_redone();
+    info!("[ {} ] Redone {}", "GUI".green(), edit);
+    Ok(())
+}
+
+/// Issue the commands of an undo or redo without recording them as new edits
+fn restore(state: &mut GUIState, commands: Vec<GUICommands>) -> Result<(), GuiError> {
+    state.history.set_restoring(true);
+    let result = commands
+        .into_iter()
+        .try_for_each(|command| execute(state, command));
+    state.history.set_restoring(false);
+    result
+}
@@ -1,48 +1,62 @@
 use colored::Colorize;
-use log::{error, info};
+use log::info;
 
 use wg_2024::network::NodeId;
 
 use messages::gui_commands::GUICommands;
 
-use crate::logic::state::GUIState;
+use crate::logic::{error::GuiError, state::GUIState};
 
-pub fn ask_for_file_list(state: &mut GUIState, client: NodeId, server: NodeId) {
-    match state.sender.send(GUICommands::AskForFileList(client, server)) {
-        Ok(()) => info!(
-            "[ {} ] Successfully sent GUICommand::AskForFileList({}, {}) from GUI to Simulation Controller",
-            "GUI".green(),
-            client,
-            server
-        ),
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::AskForFileList({}, {}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
+/// Ask a media client for the file list of a text server
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn ask_for_file_list(
+    state: &mut GUIState,
+    client: NodeId,
+    server: NodeId,
+) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::AskForFileList(client, server)) {
+        Ok(()) => {
+            info!(
+                "[ {} ] Successfully sent GUICommand::AskForFileList({}, {}) from GUI to Simulation Controller",
+                "GUI".green(),
                 client,
-                server,
-                e
+                server
             );
-        },
+            Ok(())
+        }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::AskForFileList({client}, {server})"),
+            reason: e.to_string(),
+        }),
     }
 }
 
-pub fn get_file(state: &mut GUIState, client: NodeId, server: NodeId, title: &str) {
-    match state.sender.send(GUICommands::GetFile(client, server, title.to_string())) {
-        Ok(()) => info!(
-            "[ {} ] Successfully sent GUICommand::GetFile({}, {}, {:?}) from GUI to Simulation Controller",
-            "GUI".green(),
-            client,
-            server,
-            title
-        ),
-        Err(e) => {
-            error!("[ {} ] Unable to send GUICommand::GetFile({}, {}, {:?}) from GUI to Simulation Controller: {}",
-                "GUI".red(),
+/// Ask a media client to download a file from a text server
+///
+/// # Errors
+/// Returns [`GuiError::CommandSend`] if the Simulation Controller channel is closed.
+pub fn get_file(
+    state: &mut GUIState,
+    client: NodeId,
+    server: NodeId,
+    title: &str,
+) -> Result<(), GuiError> {
+    match state.send_command(GUICommands::GetFile(client, server, title.to_string())) {
+        Ok(()) => {
+            info!(
+                "[ {} ] Successfully sent GUICommand::GetFile({}, {}, {:?}) from GUI to Simulation Controller",
+                "GUI".green(),
                 client,
                 server,
-                title,
-                e
+                title
             );
-        },
+            Ok(())
+        }
+        Err(e) => Err(GuiError::CommandSend {
+            command: format!("GUICommand::GetFile({client}, {server}, {title:?})"),
+            reason: e.to_string(),
+        }),
     }
 }
@@ -1,11 +1,17 @@
+pub mod bulk;
 pub mod chat;
+pub mod command;
 pub mod drone;
 pub mod general;
+pub mod history;
 pub mod media;
 pub mod topology;
 
+pub use bulk::{crash_selected, remove_links_between_groups, selected_drones, set_pdr_selected};
 pub use chat::{get_list, logout, register, send_message};
+pub use command::execute;
 pub use drone::{crash, set_pdr, spawn};
 pub use general::{add_sender, remove_sender};
+pub use history::{redo, undo};
 pub use media::{ask_for_file_list, get_file};
-pub use topology::topology;
+pub use topology::{snapshot, topology};
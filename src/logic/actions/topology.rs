@@ -1,7 +1,4 @@
-use eframe::egui::Color32;
-use petgraph::{graph::NodeIndex, Graph, Undirected};
-use rand::Rng;
-use std::collections::HashMap;
+use std::collections::HashSet;
 
 use colored::Colorize;
 use log::info;
@@ -9,238 +6,140 @@ use log::info;
 use wg_2024::{
     config::{Client as ConfigClient, Drone as ConfigDrone, Server as ConfigServer},
     network::NodeId,
+    packet::NodeType,
 };
 
-use messages::high_level_messages::ServerType;
+use messages::{gui_commands::GUIEvents, high_level_messages::ServerType};
 
-use crate::{
-    constants::{HEIGHT, WIDTH},
-    logic::{
-        nodes::{types::ClientType, NodeGUI},
-        state::GUIState,
-    },
+use crate::logic::{
+    error::GuiError,
+    layout::{self, Layout},
+    live_layout::LiveLayout,
+    nodes::{types::ClientType, NodeGUI},
+    state::GUIState,
 };
 
-fn fruchterman_reingold(
-    graph: &Graph<(), (), Undirected>,
-    iterations: usize,
-    max_width: f32,
-    max_height: f32,
-) -> HashMap<NodeIndex, (f32, f32)> {
-    let node_count = graph.node_count();
-    let mut positions: HashMap<NodeIndex, (f32, f32)> = HashMap::with_capacity(node_count);
-
-    // 1. Initialize node positions (randomly, within bounds)
-    let mut rng = rand::rng();
-    for node_index in graph.node_indices() {
-        positions.insert(
-            node_index,
-            (
-                rng.random_range(0.0..max_width),
-                rng.random_range(0.0..max_height),
-            ),
-        );
-    }
-
-    let k = 100.0; // Repulsion strength
-    let attraction_multiplier = 0.05; // Attraction strength
-    let mut temperature = 4.0; // Start with a high temperature
-
-    for _ in 0..iterations {
-        let mut displacements: HashMap<NodeIndex, (f32, f32)> = HashMap::with_capacity(node_count);
-        for node_index in graph.node_indices() {
-            displacements.insert(node_index, (0.0, 0.0));
-        }
-
-        let mut distances: HashMap<(NodeIndex, NodeIndex), f32> = HashMap::new();
-        for i in graph.node_indices() {
-            for j in graph.node_indices() {
-                if i != j {
-                    let dx = positions[&j].0 - positions[&i].0;
-                    let dy = positions[&j].1 - positions[&i].1;
-                    let distance = (dx * dx + dy * dy).sqrt();
-                    distances.insert((i, j), distance);
-                }
-            }
-        }
-
-        // 2. Calculate repulsive forces
-        for i in graph.node_indices() {
-            for j in graph.node_indices() {
-                if i != j {
-                    let distance = distances[&(i, j)];
-                    if distance > 0.0 {
-                        let repulsion_force = k / distance;
-                        let dx = positions[&j].0 - positions[&i].0;
-                        let dy = positions[&j].1 - positions[&i].1;
-
-                        *displacements.get_mut(&i).unwrap() = (
-                            displacements[&i].0 - repulsion_force * dx / distance,
-                            displacements[&i].1 - repulsion_force * dy / distance,
-                        );
-                        *displacements.get_mut(&j).unwrap() = (
-                            displacements[&j].0 + repulsion_force * dx / distance,
-                            displacements[&j].1 + repulsion_force * dy / distance,
-                        );
-                    }
-                }
-            }
-        }
-
-        // 3. Calculate attractive forces
-        for edge in graph.edge_indices() {
-            let (u, v) = graph.edge_endpoints(edge).unwrap();
-            let dx = positions[&v].0 - positions[&u].0;
-            let dy = positions[&v].1 - positions[&u].1;
-            let attraction_force = attraction_multiplier;
-
-            *displacements.get_mut(&u).unwrap() = (
-                displacements[&u].0 + attraction_force * dx,
-                displacements[&u].1 + attraction_force * dy,
-            );
-            *displacements.get_mut(&v).unwrap() = (
-                displacements[&v].0 - attraction_force * dx,
-                displacements[&v].1 - attraction_force * dy,
-            );
-        }
-
-        // 4. Update positions with temperature
-        let max_displacement = temperature * f32::min(max_width, max_height);
-        for node_index in graph.node_indices() {
-            let displacement = displacements.get(&node_index).unwrap();
-            let displacement_magnitude =
-                (displacement.0 * displacement.0 + displacement.1 * displacement.1).sqrt();
-
-            if displacement_magnitude > 0.0 {
-                let scale = f32::min(1.0, max_displacement / displacement_magnitude);
-                let new_x = positions[&node_index].0 + displacement.0 * scale;
-                let new_y = positions[&node_index].1 + displacement.1 * scale;
-
-                positions.insert(
-                    node_index,
-                    (new_x.clamp(25.0, max_width), new_y.clamp(125.0, max_height)),
-                );
-            }
-        }
-
-        // decrease temperature to make future changes less important
-        temperature *= 0.99;
-    }
-
-    positions
-}
-
-#[allow(clippy::missing_panics_doc)]
+/// Build the GUI nodes and the topology graph from the topology sent by the Simulation Controller
+///
+/// # Errors
+/// Returns [`GuiError::UnknownNode`] if a node is connected to an id that is not part of the topology.
 pub fn topology(
     state: &mut GUIState,
     drones: &[ConfigDrone],
     clients: &[ConfigClient],
     servers: &[ConfigServer],
-) {
-    let mut graph = Graph::<(), (), Undirected>::new_undirected();
-    let mut vertexes = HashMap::<NodeId, NodeIndex>::new();
+) -> Result<(), GuiError> {
+    // Check every link first, so an invalid topology leaves the current one untouched
+    let ids: HashSet<NodeId> = drones
+        .iter()
+        .map(|drone| drone.id)
+        .chain(clients.iter().map(|client| client.id))
+        .chain(servers.iter().map(|server| server.id))
+        .collect();
+    let links = drones
+        .iter()
+        .flat_map(|drone| &drone.connected_node_ids)
+        .chain(
+            clients
+                .iter()
+                .flat_map(|client| &client.connected_drone_ids),
+        )
+        .chain(
+            servers
+                .iter()
+                .flat_map(|server| &server.connected_drone_ids),
+        );
+    for neighbor in links {
+        if !ids.contains(neighbor) {
+            return Err(GuiError::UnknownNode(*neighbor));
+        }
+    }
+
+    state.nodes.clear();
+    state.graph.clear();
+    state.selection.reset();
+    state.layout_transition = None;
 
     for drone in drones {
-        let vertex_id = graph.add_node(());
-        vertexes.insert(drone.id, vertex_id);
+        state.graph.add_node(drone.id);
     }
 
     for client in clients {
-        let vertex_id = graph.add_node(());
-        vertexes.insert(client.id, vertex_id);
+        state.graph.add_node(client.id);
     }
 
     for server in servers {
-        let vertex_id = graph.add_node(());
-        vertexes.insert(server.id, vertex_id);
+        state.graph.add_node(server.id);
     }
 
     for drone in drones {
         for neighbor in &drone.connected_node_ids {
-            graph.add_edge(
-                *vertexes.get(&drone.id).unwrap(),
-                *vertexes.get(neighbor).unwrap(),
-                (),
-            );
+            state.graph.add_edge(drone.id, *neighbor)?;
         }
     }
 
     for client in clients {
         for neighbor in &client.connected_drone_ids {
-            graph.add_edge(
-                *vertexes.get(&client.id).unwrap(),
-                *vertexes.get(neighbor).unwrap(),
-                (),
-            );
+            state.graph.add_edge(client.id, *neighbor)?;
         }
     }
 
     for server in servers {
         for neighbor in &server.connected_drone_ids {
-            #[allow(clippy::missing_panics_doc)]
-            graph.add_edge(
-                *vertexes.get(&server.id).unwrap(),
-                *vertexes.get(neighbor).unwrap(),
-                (),
-            );
+            state.graph.add_edge(server.id, *neighbor)?;
         }
     }
 
-    let coordinates = fruchterman_reingold(&graph, 500, WIDTH - 25.0, HEIGHT - 25.0);
+    // Reuse the saved positions of this topology, otherwise scatter the nodes and let the live
+    // layout move them into place
+    let saved = state
+        .saved_positions
+        .as_ref()
+        .filter(|saved| saved.matches(&state.graph))
+        .map(|saved| saved.scaled(state.canvas_size));
+    let (layout, coordinates) = match saved {
+        Some(coordinates) => (Layout::Saved, coordinates),
+        None => (
+            Layout::ForceDirected,
+            layout::scatter(state.graph.nodes(), state.canvas_size, state.layout_seed),
+        ),
+    };
+    state.layout = layout;
+    state.layout_canvas = state.canvas_size;
+    state.live_layout = (layout == Layout::ForceDirected).then(LiveLayout::start);
 
     for drone in drones {
-        let (x, y) = coordinates.get(vertexes.get(&drone.id).unwrap()).unwrap();
-        let new_drone = NodeGUI::new_drone(drone, *x, *y);
-
-        for drone in new_drone.neighbor.clone() {
-            if !state.edges.contains_key(&drone) {
-                let (vec, _) = state
-                    .edges
-                    .entry(new_drone.id)
-                    .or_insert_with(|| (Vec::new(), Color32::GRAY));
-                vec.push(drone);
-            }
-        }
-
+        let (x, y) = coordinates[&drone.id];
+        let new_drone = NodeGUI::new_drone(drone, x, y);
         state.nodes.insert(new_drone.id, new_drone);
     }
 
     let half = clients.len() / 2;
     for (count, client) in clients.iter().enumerate() {
-        let (x, y) = coordinates.get(vertexes.get(&client.id).unwrap()).unwrap();
+        let (x, y) = coordinates[&client.id];
         let new_client = if count < half {
-            NodeGUI::new_client(client, *x, *y, Some(ClientType::Chat))
+            NodeGUI::new_client(client, x, y, Some(ClientType::Chat))
         } else {
-            NodeGUI::new_client(client, *x, *y, Some(ClientType::Media))
+            NodeGUI::new_client(client, x, y, Some(ClientType::Media))
         };
 
-        state
-            .edges
-            .entry(new_client.id)
-            .or_insert_with(|| (Vec::new(), Color32::GRAY));
-
         state.nodes.insert(new_client.id, new_client);
     }
 
     let third = servers.len() / 3;
     let mut count = servers.len();
     for server in servers {
-        let (x, y) = coordinates.get(vertexes.get(&server.id).unwrap()).unwrap();
+        let (x, y) = coordinates[&server.id];
 
         let new_server;
         if count > (third * 2) {
-            new_server = NodeGUI::new_server(server, *x, *y, Some(ServerType::Text));
+            new_server = NodeGUI::new_server(server, x, y, Some(ServerType::Text));
         } else if count > third {
-            new_server = NodeGUI::new_server(server, *x, *y, Some(ServerType::Media));
+            new_server = NodeGUI::new_server(server, x, y, Some(ServerType::Media));
         } else {
-            new_server = NodeGUI::new_server(server, *x, *y, Some(ServerType::Chat));
+            new_server = NodeGUI::new_server(server, x, y, Some(ServerType::Chat));
         }
 
-        state
-            .edges
-            .entry(new_server.id)
-            .or_insert_with(|| (Vec::new(), Color32::GRAY));
-
         state.nodes.insert(new_server.id, new_server);
 
         count -= 1;
@@ -248,4 +147,64 @@ pub fn topology(
 
     info!("[ {} ] Successfully composed the topology", "GUI".green());
     state.initialized = true;
+
+    Ok(())
+}
+
+/// Rebuild a `GUIEvents::Topology` from the current state.
+///
+/// Clients and servers are ordered so that [`topology`] assigns them the same types again.
+#[must_use]
+pub fn snapshot(state: &GUIState) -> GUIEvents {
+    let mut nodes: Vec<&NodeGUI> = state.nodes.values().collect();
+    nodes.sort_by_key(|node| node.id);
+
+    let drones = nodes
+        .iter()
+        .filter(|node| node.node_type == NodeType::Drone)
+        .map(|node| ConfigDrone {
+            id: node.id,
+            connected_node_ids: state.graph.neighbors(node.id),
+            pdr: node.pdr,
+        })
+        .collect();
+
+    let client_rank = |node: &NodeGUI| match node.client_type {
+        Some(ClientType::Chat) => 0,
+        _ => 1,
+    };
+    let mut clients: Vec<&NodeGUI> = nodes
+        .iter()
+        .copied()
+        .filter(|node| node.node_type == NodeType::Client)
+        .collect();
+    clients.sort_by_key(|node| client_rank(node));
+    let clients = clients
+        .into_iter()
+        .map(|node| ConfigClient {
+            id: node.id,
+            connected_drone_ids: state.graph.neighbors(node.id),
+        })
+        .collect();
+
+    let server_rank = |node: &NodeGUI| match node.server_type {
+        Some(ServerType::Text) => 0,
+        Some(ServerType::Media) => 1,
+        _ => 2,
+    };
+    let mut servers: Vec<&NodeGUI> = nodes
+        .iter()
+        .copied()
+        .filter(|node| node.node_type == NodeType::Server)
+        .collect();
+    servers.sort_by_key(|node| server_rank(node));
+    let servers = servers
+        .into_iter()
+        .map(|node| ConfigServer {
+            id: node.id,
+            connected_drone_ids: state.graph.neighbors(node.id),
+        })
+        .collect();
+
+    GUIEvents::Topology(drones, clients, servers)
 }
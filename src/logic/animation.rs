@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+
+use eframe::egui::Color32;
+
+use wg_2024::{
+    network::NodeId,
+    packet::{Packet, PacketType},
+};
+
+use crate::{
+    constants::{MAX_PACKET_MARKERS, PACKET_ANIMATION_DURATION_SECS},
+    logic::{routes::TraceKey, traffic::PacketKind},
+};
+
+/// Progress along the edge at which a dropped packet bursts
+pub const BURST_PROGRESS: f32 = 0.5;
+
+/// A packet travelling along the edge from `src` to `dest`
+#[derive(Clone, Debug)]
+pub struct PacketMarker {
+    pub src: NodeId,
+    pub dest: NodeId,
+    pub color: Color32,
+    /// `0.0` at `src`, `1.0` at `dest`
+    pub progress: f32,
+    pub dropped: bool,
+    /// Session the packet belongs to, for tracing its route
+    pub trace: Option<TraceKey>,
+}
+
+impl PacketMarker {
+    /// Position along the edge, which stops at the burst point for dropped packets
+    #[must_use]
+    pub fn position(&self) -> f32 {
+        if self.dropped {
+            self.progress.min(BURST_PROGRESS)
+        } else {
+            self.progress.min(1.0)
+        }
+    }
+
+    /// Progress of the burst of a dropped packet, from `0.0` to `1.0`
+    #[must_use]
+    pub fn burst(&self) -> Option<f32> {
+        (self.dropped && self.progress >= BURST_PROGRESS)
+            .then(|| ((self.progress - BURST_PROGRESS) / (1.0 - BURST_PROGRESS)).min(1.0))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.progress >= 1.0
+    }
+}
+
+/// Color of a packet marker
+#[must_use]
+pub fn packet_color(pack_type: &PacketType) -> Color32 {
+    kind_color(PacketKind::of(pack_type))
+}
+
+/// Color used for packets of `kind`, in the animations and the dashboard
+#[must_use]
+pub fn kind_color(kind: PacketKind) -> Color32 {
+    match kind {
+        PacketKind::MsgFragment => Color32::LIGHT_BLUE,
+        PacketKind::Ack => Color32::GREEN,
+        PacketKind::Nack => Color32::LIGHT_RED,
+        PacketKind::FloodRequest => Color32::WHITE,
+        PacketKind::FloodResponse => Color32::LIGHT_GRAY,
+    }
+}
+
+/// Packets currently drawn travelling along the edges
+#[derive(Clone, Debug)]
+pub struct PacketAnimations {
+    /// Edges travelled per second
+    pub speed: f32,
+    /// Maximum number of markers drawn at once, the oldest are discarded first
+    pub max_markers: usize,
+    markers: VecDeque<PacketMarker>,
+}
+
+impl Default for PacketAnimations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PacketAnimations {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            speed: 1.0 / PACKET_ANIMATION_DURATION_SECS,
+            max_markers: MAX_PACKET_MARKERS,
+            markers: VecDeque::new(),
+        }
+    }
+
+    /// Start a marker for a packet sent from `src` to `dest`
+    pub fn push_sent(&mut self, src: NodeId, dest: NodeId, packet: &Packet) {
+        self.push(PacketMarker {
+            src,
+            dest,
+            color: packet_color(&packet.pack_type),
+            progress: 0.0,
+            dropped: false,
+            trace: TraceKey::of(packet),
+        });
+    }
+
+    /// Burst the marker of a packet dropped by `dropper`, or start a new one bursting
+    /// on the edge it came from
+    pub fn push_dropped(&mut self, dropper: NodeId, packet: &Packet) {
+        let hops = &packet.routing_header.hops;
+        let src = hops
+            .iter()
+            .position(|hop| *hop == dropper)
+            .and_then(|position| position.checked_sub(1))
+            .map_or(dropper, |previous| hops[previous]);
+
+        if let Some(marker) = self
+            .markers
+            .iter_mut()
+            .rev()
+            .find(|marker| (marker.src, marker.dest) == (src, dropper) && !marker.dropped)
+        {
+            marker.dropped = true;
+            return;
+        }
+
+        self.push(PacketMarker {
+            src,
+            dest: dropper,
+            color: packet_color(&packet.pack_type),
+            progress: 0.0,
+            dropped: true,
+            trace: TraceKey::of(packet),
+        });
+    }
+
+    /// Move every marker forward by `dt` seconds, removing the finished ones
+    pub fn advance(&mut self, dt: f32) {
+        let step = dt * self.speed;
+        for marker in &mut self.markers {
+            marker.progress += step;
+        }
+        self.markers.retain(|marker| !marker.is_finished());
+    }
+
+    pub fn markers(&self) -> impl Iterator<Item = &PacketMarker> {
+        self.markers.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.markers.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.markers.clear();
+    }
+
+    fn push(&mut self, marker: PacketMarker) {
+        if self.max_markers == 0 {
+            return;
+        }
+        while self.markers.len() >= self.max_markers {
+            self.markers.pop_front();
+        }
+        self.markers.push_back(marker);
+    }
+}
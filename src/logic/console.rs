@@ -0,0 +1,479 @@
+use wg_2024::{network::NodeId, packet::NodeType};
+
+use messages::gui_commands::GUICommands;
+
+use crate::{
+    constants::{MAX_CONSOLE_HISTORY, MAX_CONSOLE_OUTPUT},
+    logic::{actions, error::GuiError, state::GUIState},
+};
+
+/// Kind of argument expected by a console command, used for autocompletion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Arg {
+    Drone,
+    Node,
+    NodeList,
+    Client,
+    Server,
+    Free,
+}
+
+/// Console commands: name, arguments, usage and description
+const COMMANDS: &[(&str, &[Arg], &str, &str)] = &[
+    ("crash", &[Arg::Drone], "crash <drone>", "Crash a drone"),
+    (
+        "pdr",
+        &[Arg::Drone, Arg::Free],
+        "pdr <drone> <pdr>",
+        "Set the packet drop rate of a drone",
+    ),
+    (
+        "link",
+        &[Arg::Node, Arg::Node],
+        "link <node> <node>",
+        "Add a link between two nodes",
+    ),
+    (
+        "unlink",
+        &[Arg::Node, Arg::Node],
+        "unlink <node> <node>",
+        "Remove the link between two nodes",
+    ),
+    (
+        "spawn",
+        &[Arg::Free, Arg::NodeList, Arg::Free],
+        "spawn <id> <n1,n2,...> <pdr>",
+        "Spawn a drone",
+    ),
+    (
+        "msg",
+        &[Arg::Client, Arg::Client, Arg::Free],
+        "msg <client> <client> <text>",
+        "Send a chat message",
+    ),
+    (
+        "register",
+        &[Arg::Client, Arg::Server],
+        "register <client> <server>",
+        "Register a chat client to a server",
+    ),
+    (
+        "clients",
+        &[Arg::Client],
+        "clients <client>",
+        "Ask a chat client for the client list",
+    ),
+    (
+        "logout",
+        &[Arg::Client, Arg::Server],
+        "logout <client> <server>",
+        "Log a chat client out of a server",
+    ),
+    (
+        "files",
+        &[Arg::Client, Arg::Server],
+        "files <client> <server>",
+        "Ask a media client for the file list of a server",
+    ),
+    (
+        "get",
+        &[Arg::Client, Arg::Server, Arg::Free],
+        "get <client> <server> <title>",
+        "Ask a media client to download a file",
+    ),
+    ("undo", &[], "undo", "Revert the latest topology edit"),
+    ("redo", &[], "redo", "Issue again the latest undone edit"),
+    ("help", &[], "help", "List the available commands"),
+    ("clear", &[], "clear", "Clear the console output"),
+];
+
+/// Parse a console line into the `GUICommands` it stands for
+///
+/// # Errors
+/// Returns [`GuiError::InvalidInput`] if the line is not a valid command.
+pub fn parse(line: &str) -> Result<GUICommands, GuiError> {
+    let mut tokens = line.split_whitespace();
+    let name = tokens
+        .next()
+        .ok_or_else(|| GuiError::InvalidInput("Empty command".to_string()))?;
+    let args: Vec<&str> = tokens.collect();
+
+    let usage = COMMANDS
+        .iter()
+        .find(|(command, _, _, _)| *command == name)
+        .map(|(_, _, usage, _)| *usage)
+        .ok_or_else(|| GuiError::InvalidInput(format!("Unknown command {name:?}, try `help`")))?;
+
+    let arity = |expected: usize| {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(GuiError::InvalidInput(format!("Usage: {usage}")))
+        }
+    };
+    let at_least = |expected: usize| {
+        if args.len() >= expected {
+            Ok(())
+        } else {
+            Err(GuiError::InvalidInput(format!("Usage: {usage}")))
+        }
+    };
+
+    let command = match name {
+        "crash" => {
+            arity(1)?;
+            GUICommands::Crash(node(args[0])?)
+        }
+        "pdr" => {
+            arity(2)?;
+            GUICommands::SetPDR(node(args[0])?, pdr(args[1])?)
+        }
+        "link" => {
+            arity(2)?;
+            GUICommands::AddSender(node(args[0])?, node(args[1])?)
+        }
+        "unlink" => {
+            arity(2)?;
+            GUICommands::RemoveSender(node(args[0])?, node(args[1])?)
+        }
+        "spawn" => {
+            arity(3)?;
+            let neighbors = args[1]
+                .split(',')
+                .filter(|id| !id.is_empty())
+                .map(node)
+                .collect::<Result<Vec<_>, _>>()?;
+            GUICommands::Spawn(node(args[0])?, neighbors, pdr(args[2])?)
+        }
+        "msg" => {
+            at_least(3)?;
+            GUICommands::SendMessageTo(node(args[0])?, node(args[1])?, rest(line, 3).to_string())
+        }
+        "register" => {
+            arity(2)?;
+            GUICommands::RegisterTo(node(args[0])?, node(args[1])?)
+        }
+        "clients" => {
+            arity(1)?;
+            GUICommands::GetClientList(node(args[0])?)
+        }
+        "logout" => {
+            arity(2)?;
+            GUICommands::LogOut(node(args[0])?, node(args[1])?)
+        }
+        "files" => {
+            arity(2)?;
+            GUICommands::AskForFileList(node(args[0])?, node(args[1])?)
+        }
+        "get" => {
+            at_least(3)?;
+            GUICommands::GetFile(node(args[0])?, node(args[1])?, rest(line, 3).to_string())
+        }
+        _ => {
+            return Err(GuiError::InvalidInput(format!(
+                "{name:?} does not issue a command"
+            )))
+        }
+    };
+
+    Ok(command)
+}
+
+/// The raw text of `line` after its first `skip` tokens, keeping its own spacing
+fn rest(line: &str, skip: usize) -> &str {
+    let mut rest = line.trim_start();
+    for _ in 0..skip {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        rest = rest[end..].trim_start();
+    }
+    rest
+}
+
+fn node(token: &str) -> Result<NodeId, GuiError> {
+    token
+        .parse()
+        .map_err(|_| GuiError::InvalidInput(format!("Invalid node id {token:?}")))
+}
+
+fn pdr(token: &str) -> Result<f32, GuiError> {
+    token
+        .parse()
+        .map_err(|_| GuiError::InvalidInput(format!("Invalid PDR {token:?}")))
+}
+
+/// Kind of a line in the console output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    Input,
+    Info,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConsoleLine {
+    pub text: String,
+    pub kind: LineKind,
+}
+
+/// Text console driving the simulation through [`actions`]
+#[derive(Clone, Debug, Default)]
+pub struct Console {
+    pub input: String,
+    pub suggestions: Vec<String>,
+    history: Vec<String>,
+    history_cursor: Option<usize>,
+    output: Vec<ConsoleLine>,
+}
+
+impl Console {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn output(&self) -> &[ConsoleLine] {
+        &self.output
+    }
+
+    /// Run the current input line
+    pub fn submit(&mut self, state: &mut GUIState) {
+        let line = self.input.trim().to_string();
+        self.input.clear();
+        self.suggestions.clear();
+        self.history_cursor = None;
+
+        if line.is_empty() {
+            return;
+        }
+        if self.history.last() != Some(&line) {
+            if self.history.len() >= MAX_CONSOLE_HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(line.clone());
+        }
+        self.push(LineKind::Input, format!("> {line}"));
+
+        match line.as_str() {
+            "help" => {
+                for (_, _, usage, description) in COMMANDS {
+                    self.push(LineKind::Info, format!("{usage:<32} {description}"));
+                }
+            }
+            "clear" => self.output.clear(),
+            "undo" => match actions::undo(state) {
+                Ok(()) => self.push(LineKind::Info, "Undone".to_string()),
+                Err(e) => self.push(LineKind::Error, e.to_string()),
+            },
+            "redo" => match actions::redo(state) {
+                Ok(()) => self.push(LineKind::Info, "Redone".to_string()),
+                Err(e) => self.push(LineKind::Error, e.to_string()),
+            },
+            _ => match parse(&line).and_then(|command| {
+                let text = format!("{command:?}");
+                actions::execute(state, command).map(|()| text)
+            }) {
+                Ok(text) => self.push(LineKind::Info, format!("Sent {text}")),
+                Err(e) => self.push(LineKind::Error, e.to_string()),
+            },
+        }
+    }
+
+    /// Replace the input with the previous history entry
+    pub fn history_previous(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_cursor {
+            Some(index) => index.saturating_sub(1),
+            None => self.history.len() - 1,
+        };
+        self.history_cursor = Some(index);
+        self.input.clone_from(&self.history[index]);
+    }
+
+    /// Replace the input with the next history entry, or clear it past the newest one
+    pub fn history_next(&mut self) {
+        match self.history_cursor {
+            Some(index) if index + 1 < self.history.len() => {
+                self.history_cursor = Some(index + 1);
+                self.input.clone_from(&self.history[index + 1]);
+            }
+            Some(_) => {
+                self.history_cursor = None;
+                self.input.clear();
+            }
+            None => (),
+        }
+    }
+
+    /// Complete the token under the cursor (the last one) with command names or node ids
+    pub fn complete(&mut self, state: &GUIState) {
+        let ends_with_space = self.input.ends_with(char::is_whitespace);
+        let tokens: Vec<&str> = self.input.split_whitespace().collect();
+        let position = if ends_with_space || tokens.is_empty() {
+            tokens.len()
+        } else {
+            tokens.len() - 1
+        };
+        let current = if ends_with_space {
+            ""
+        } else {
+            tokens.last().copied().unwrap_or_default()
+        };
+
+        let (prefix, candidates, separator) = if position == 0 {
+            let candidates = COMMANDS
+                .iter()
+                .map(|(name, _, _, _)| (*name).to_string())
+                .collect();
+            (current, candidates, " ")
+        } else {
+            let arg = COMMANDS
+                .iter()
+                .find(|(name, _, _, _)| *name == tokens[0])
+                .and_then(|(_, args, _, _)| args.get(position - 1).copied())
+                .unwrap_or(Arg::Free);
+            match arg {
+                Arg::Free => {
+                    self.suggestions.clear();
+                    return;
+                }
+                // Only the id after the last comma is completed
+                Arg::NodeList => {
+                    let prefix = current.rsplit(',').next().unwrap_or_default();
+                    (prefix, Self::node_ids(state, arg), "")
+                }
+                _ => (current, Self::node_ids(state, arg), " "),
+            }
+        };
+
+        let matches: Vec<String> = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(prefix))
+            .collect();
+
+        let head_len = self.input.len() - prefix.len();
+        match matches.as_slice() {
+            [] => self.suggestions.clear(),
+            [single] => {
+                self.input = format!("{}{single}{separator}", &self.input[..head_len]);
+                self.suggestions.clear();
+            }
+            _ => {
+                let common = common_prefix(&matches);
+                self.input = format!("{}{common}", &self.input[..head_len]);
+                self.suggestions = matches;
+            }
+        }
+    }
+
+    fn node_ids(state: &GUIState, arg: Arg) -> Vec<String> {
+        let mut ids: Vec<NodeId> = state
+            .nodes
+            .values()
+            .filter(|node| match arg {
+                Arg::Drone => node.node_type == NodeType::Drone,
+                Arg::Client => node.node_type == NodeType::Client,
+                Arg::Server => node.node_type == NodeType::Server,
+                Arg::Node | Arg::NodeList | Arg::Free => true,
+            })
+            .map(|node| node.id)
+            .collect();
+        ids.sort_unstable();
+        ids.into_iter().map(|id| id.to_string()).collect()
+    }
+
+    fn push(&mut self, kind: LineKind, text: String) {
+        if self.output.len() >= MAX_CONSOLE_OUTPUT {
+            self.output.remove(0);
+        }
+        self.output.push(ConsoleLine { text, kind });
+    }
+}
+
+fn common_prefix(words: &[String]) -> String {
+    let Some(first) = words.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for word in &words[1..] {
+        len = first
+            .bytes()
+            .zip(word.bytes())
+            .take(len)
+            .take_while(|(a, b)| a == b)
+            .count();
+    }
+    first[..len].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::fixtures::initialized_state;
+
+    #[test]
+    fn parses_commands() {
+        assert!(matches!(parse("crash 3"), Ok(GUICommands::Crash(3))));
+        assert!(matches!(
+            parse("  link 1   2 "),
+            Ok(GUICommands::AddSender(1, 2))
+        ));
+        assert!(matches!(
+            parse("spawn 42 1,2,,3 0.25"),
+            Ok(GUICommands::Spawn(42, ref neighbors, pdr))
+                if neighbors == &[1, 2, 3] && (pdr - 0.25).abs() < f32::EPSILON
+        ));
+    }
+
+    #[test]
+    fn keeps_the_raw_text_of_messages_and_titles() {
+        assert!(matches!(
+            parse("msg 10 11 hello   there\tfriend"),
+            Ok(GUICommands::SendMessageTo(10, 11, ref text)) if text == "hello   there\tfriend"
+        ));
+        assert!(matches!(
+            parse("get 10 20  my  file.txt"),
+            Ok(GUICommands::GetFile(10, 20, ref title)) if title == "my  file.txt"
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        for line in [
+            "",
+            "fly 1",
+            "crash",
+            "crash x",
+            "pdr 1",
+            "link 1 2 3",
+            "msg 10 11",
+        ] {
+            assert!(
+                matches!(parse(line), Err(GuiError::InvalidInput(_))),
+                "{line:?}"
+            );
+        }
+        assert!(parse("undo").is_err());
+    }
+
+    #[test]
+    fn keeps_a_bounded_output_and_history() {
+        let mut state = initialized_state();
+        let mut console = Console::new();
+        for i in 0..MAX_CONSOLE_OUTPUT {
+            console.input = format!("bogus {i}");
+            console.submit(&mut state);
+        }
+
+        assert_eq!(console.output().len(), MAX_CONSOLE_OUTPUT);
+        assert_eq!(
+            console.output()[0].text,
+            format!("> bogus {}", MAX_CONSOLE_OUTPUT / 2)
+        );
+        assert_eq!(console.history.len(), MAX_CONSOLE_HISTORY);
+        console.history_previous();
+        assert_eq!(console.input, format!("bogus {}", MAX_CONSOLE_OUTPUT - 1));
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    fmt,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use wg_2024::network::NodeId;
+
+/// Errors raised while applying `GUIEvents` or issuing `GUICommands`
+#[derive(Clone, Debug, PartialEq)]
+pub enum GuiError {
+    /// An event or command referenced a node the GUI does not know (or that already crashed)
+    UnknownNode(NodeId),
+    /// A `GUICommands` could not be delivered to the Simulation Controller
+    CommandSend { command: String, reason: String },
+    /// The Simulation Controller did not confirm a command in time
+    CommandTimeout { command: String, timeout: Duration },
+    /// The `GUIEvents` channel from the Simulation Controller was closed
+    ChannelDisconnected,
+    /// An event arrived that is not valid in the current state
+    UnexpectedEvent(String),
+    /// User input could not be turned into a command
+    InvalidInput(String),
+    /// A file could not be read or written
+    File { path: String, reason: String },
+    /// The nodes and the topology graph disagree
+    InconsistentTopology(String),
+    /// An edit that cannot be reverted was asked to be undone
+    Irreversible(String),
+    /// A bulk action stopped on `error` after sending `done` of its `total` commands
+    Incomplete {
+        action: String,
+        done: usize,
+        total: usize,
+        error: Box<GuiError>,
+    },
+}
+
+impl fmt::Display for GuiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownNode(id) => write!(f, "Unknown node {id}"),
+            Self::CommandSend { command, reason } => {
+                write!(
+                    f,
+                    "Unable to send {command} to Simulation Controller: {reason}"
+                )
+            }
+            Self::CommandTimeout { command, timeout } => write!(
+                f,
+                "{command} was not confirmed by Simulation Controller within {}s",
+                timeout.as_secs_f32()
+            ),
+            Self::ChannelDisconnected => {
+                write!(
+                    f,
+                    "GUIEvents channel from Simulation Controller disconnected"
+                )
+            }
+            Self::UnexpectedEvent(event) => write!(f, "Unexpected event: {event}"),
+            Self::InvalidInput(reason) => write!(f, "Invalid input: {reason}"),
+            Self::File { path, reason } => write!(f, "File {path}: {reason}"),
+            Self::InconsistentTopology(reason) => write!(f, "Inconsistent topology: {reason}"),
+            Self::Irreversible(command) => write!(f, "{command} cannot be undone"),
+            Self::Incomplete {
+                action,
+                done,
+                total,
+                error,
+            } => write!(f, "{action} stopped after {done} of {total}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for GuiError {}
+
+impl GuiError {
+    /// Build a [`GuiError::File`] for an I/O or (de)serialization failure on `path`
+    #[must_use]
+    pub fn file(path: &Path, error: &impl fmt::Display) -> Self {
+        Self::File {
+            path: path.display().to_string(),
+            reason: error.to_string(),
+        }
+    }
+}
+
+/// An error shown in the error panel until the user dismisses it
+#[derive(Clone, Debug)]
+pub struct ErrorRecord {
+    pub error: GuiError,
+    pub last_seen: Instant,
+    pub occurrences: usize,
+}
+
+impl ErrorRecord {
+    #[must_use]
+    pub fn new(error: GuiError) -> Self {
+        Self {
+            error,
+            last_seen: Instant::now(),
+            occurrences: 1,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Small network shared by the unit tests
+
+use crossbeam_channel::unbounded;
+use wg_2024::config::{Client, Config, Drone, Server};
+
+use messages::gui_commands::GUIEvents;
+
+use crate::logic::{handlers::EventHandler, state::GUIState};
+
+/// Drones 1 and 2 linked together, client 10 behind drone 1 and server 20 behind drone 2
+pub fn config() -> Config {
+    Config {
+        drone: vec![
+            Drone {
+                id: 1,
+                connected_node_ids: vec![2, 10],
+                pdr: 0.1,
+            },
+            Drone {
+                id: 2,
+                connected_node_ids: vec![1, 20],
+                pdr: 0.1,
+            },
+        ],
+        client: vec![Client {
+            id: 10,
+            connected_drone_ids: vec![1],
+        }],
+        server: vec![Server {
+            id: 20,
+            connected_drone_ids: vec![2],
+        }],
+    }
+}
+
+/// The topology of [`config`], as sent by the Simulation Controller
+pub fn topology() -> GUIEvents {
+    let config = config();
+    GUIEvents::Topology(config.drone, config.client, config.server)
+}
+
+/// A state initialized with [`topology`], whose channels are not connected to anything
+pub fn initialized_state() -> GUIState {
+    let (sender, _) = unbounded();
+    let (_, receiver) = unbounded();
+    let mut state = GUIState::new(sender, receiver);
+    EventHandler::apply_event(&mut state, topology()).unwrap();
+    state
+}
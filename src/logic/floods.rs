@@ -0,0 +1,316 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use wg_2024::{
+    network::NodeId,
+    packet::{FloodRequest, FloodResponse, Packet, PacketType},
+};
+
+use crate::constants::MAX_FLOOD_TRACES;
+
+/// A network discovery, identified by its id and the node that started it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FloodKey {
+    pub flood_id: u64,
+    pub initiator: NodeId,
+}
+
+/// A node reached by a flood request
+#[derive(Clone, Copy, Debug)]
+pub struct FloodReach {
+    /// Hops from the initiator
+    pub depth: usize,
+    /// Node the request first came from, `None` for the initiator
+    pub parent: Option<NodeId>,
+    pub at: Instant,
+}
+
+/// Path trace carried back to the initiator by a flood response
+#[derive(Clone, Debug)]
+pub struct FloodResponseTrace {
+    pub path: Vec<NodeId>,
+    /// Whether the response reached the initiator
+    pub returned: bool,
+}
+
+/// How a flood request spread through the network, and the responses it got
+#[derive(Clone, Debug)]
+pub struct Flood {
+    pub started: Instant,
+    pub last_seen: Instant,
+    pub reached: HashMap<NodeId, FloodReach>,
+    pub responses: Vec<FloodResponseTrace>,
+}
+
+impl Flood {
+    fn new(initiator: NodeId) -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            last_seen: now,
+            reached: HashMap::from([(
+                initiator,
+                FloodReach {
+                    depth: 0,
+                    parent: None,
+                    at: now,
+                },
+            )]),
+            responses: Vec::new(),
+        }
+    }
+
+    /// Time from the first request to the last request or response seen
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.last_seen - self.started
+    }
+
+    /// Hops from the initiator to the furthest node reached
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.reached
+            .values()
+            .map(|reach| reach.depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Links the request first reached each node through, from parent to child
+    pub fn tree(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.reached
+            .iter()
+            .filter_map(|(id, reach)| Some((reach.parent?, *id)))
+    }
+
+    #[must_use]
+    pub fn returned_responses(&self) -> usize {
+        self.responses
+            .iter()
+            .filter(|response| response.returned)
+            .count()
+    }
+
+    fn record_request(&mut self, src: NodeId, dest: NodeId, request: &FloodRequest) {
+        self.last_seen = Instant::now();
+        if self.reached.contains_key(&dest) {
+            return;
+        }
+        // The path trace ends with the sender, so its length is the depth of the receiver
+        let depth = self
+            .reached
+            .get(&src)
+            .map_or(request.path_trace.len(), |reach| reach.depth + 1);
+        self.reached.insert(
+            dest,
+            FloodReach {
+                depth,
+                parent: Some(src),
+                at: self.last_seen,
+            },
+        );
+    }
+
+    fn record_response(&mut self, dest: NodeId, initiator: NodeId, response: &FloodResponse) {
+        self.last_seen = Instant::now();
+        let path: Vec<NodeId> = response.path_trace.iter().map(|(id, _)| *id).collect();
+        let returned = dest == initiator;
+
+        match self.responses.iter_mut().find(|known| known.path == path) {
+            Some(known) => known.returned |= returned,
+            None => self.responses.push(FloodResponseTrace { path, returned }),
+        }
+    }
+}
+
+/// Floods seen recently, and the one whose tree is drawn on the canvas
+#[derive(Clone, Debug, Default)]
+pub struct FloodTraces {
+    pub selected: Option<FloodKey>,
+    floods: HashMap<FloodKey, Flood>,
+}
+
+impl FloodTraces {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a flood request or response sent from `src` to `dest`, other packets are
+    /// ignored
+    pub fn record_sent(&mut self, src: NodeId, dest: NodeId, packet: &Packet) {
+        match &packet.pack_type {
+            PacketType::FloodRequest(request) => {
+                let key = FloodKey {
+                    flood_id: request.flood_id,
+                    initiator: request.initiator_id,
+                };
+                self.flood_mut(key).record_request(src, dest, request);
+            }
+            PacketType::FloodResponse(response) => {
+                let Some((initiator, _)) = response.path_trace.first() else {
+                    return;
+                };
+                let key = FloodKey {
+                    flood_id: response.flood_id,
+                    initiator: *initiator,
+                };
+                self.flood_mut(key)
+                    .record_response(dest, *initiator, response);
+            }
+            _ => {}
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &FloodKey) -> Option<&Flood> {
+        self.floods.get(key)
+    }
+
+    /// The flood drawn on the canvas, if it is still recorded
+    #[must_use]
+    pub fn selected(&self) -> Option<&Flood> {
+        self.selected.as_ref().and_then(|key| self.floods.get(key))
+    }
+
+    /// Every flood, most recently started first
+    #[must_use]
+    pub fn recent(&self) -> Vec<(FloodKey, &Flood)> {
+        let mut floods: Vec<(FloodKey, &Flood)> = self
+            .floods
+            .iter()
+            .map(|(key, flood)| (*key, flood))
+            .collect();
+        floods.sort_by_key(|(_, flood)| std::cmp::Reverse(flood.started));
+        floods
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.floods.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.floods.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.floods.clear();
+        self.selected = None;
+    }
+
+    fn flood_mut(&mut self, key: FloodKey) -> &mut Flood {
+        if !self.floods.contains_key(&key) {
+            self.evict();
+        }
+        self.floods
+            .entry(key)
+            .or_insert_with(|| Flood::new(key.initiator))
+    }
+
+    /// Make room for a new flood by forgetting the oldest one, unless it is selected
+    fn evict(&mut self) {
+        if self.floods.len() < MAX_FLOOD_TRACES {
+            return;
+        }
+        let oldest = self
+            .floods
+            .iter()
+            .filter(|(key, _)| Some(**key) != self.selected)
+            .min_by_key(|(_, flood)| flood.started)
+            .map(|(key, _)| *key);
+        if let Some(key) = oldest {
+            self.floods.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wg_2024::{network::SourceRoutingHeader, packet::NodeType};
+
+    use super::*;
+
+    fn request(flood_id: u64, path_trace: &[(NodeId, NodeType)]) -> Packet {
+        Packet {
+            pack_type: PacketType::FloodRequest(FloodRequest {
+                flood_id,
+                initiator_id: path_trace[0].0,
+                path_trace: path_trace.to_vec(),
+            }),
+            routing_header: SourceRoutingHeader::default(),
+            session_id: 0,
+        }
+    }
+
+    fn response(flood_id: u64, path_trace: &[(NodeId, NodeType)]) -> Packet {
+        Packet {
+            pack_type: PacketType::FloodResponse(FloodResponse {
+                flood_id,
+                path_trace: path_trace.to_vec(),
+            }),
+            routing_header: SourceRoutingHeader::default(),
+            session_id: 0,
+        }
+    }
+
+    const KEY: FloodKey = FloodKey {
+        flood_id: 1,
+        initiator: 10,
+    };
+
+    #[test]
+    fn builds_the_tree_the_request_spread_through() {
+        let mut floods = FloodTraces::new();
+        let client = (10, NodeType::Client);
+        let drone = (1, NodeType::Drone);
+
+        floods.record_sent(10, 1, &request(1, &[client]));
+        floods.record_sent(1, 2, &request(1, &[client, drone]));
+        // A request coming back to a reached node does not change its parent
+        floods.record_sent(2, 1, &request(1, &[client, drone, (2, NodeType::Drone)]));
+
+        let flood = floods.get(&KEY).unwrap();
+        assert_eq!(flood.depth(), 2);
+        assert_eq!(flood.reached[&1].parent, Some(10));
+        let mut tree: Vec<(NodeId, NodeId)> = flood.tree().collect();
+        tree.sort_unstable();
+        assert_eq!(tree, vec![(1, 2), (10, 1)]);
+    }
+
+    #[test]
+    fn responses_are_returned_once_they_reach_the_initiator() {
+        let mut floods = FloodTraces::new();
+        let path = [
+            (10, NodeType::Client),
+            (1, NodeType::Drone),
+            (20, NodeType::Server),
+        ];
+
+        floods.record_sent(20, 1, &response(1, &path));
+        let flood = floods.get(&KEY).unwrap();
+        assert_eq!((flood.responses.len(), flood.returned_responses()), (1, 0));
+
+        floods.record_sent(1, 10, &response(1, &path));
+        let flood = floods.get(&KEY).unwrap();
+        assert_eq!((flood.responses.len(), flood.returned_responses()), (1, 1));
+        assert_eq!(flood.responses[0].path, vec![10, 1, 20]);
+    }
+
+    #[test]
+    fn keeps_the_selected_flood_when_full() {
+        let mut floods = FloodTraces::new();
+        floods.record_sent(10, 1, &request(1, &[(10, NodeType::Client)]));
+        floods.selected = Some(KEY);
+
+        for flood_id in 2..=MAX_FLOOD_TRACES as u64 + 1 {
+            floods.record_sent(10, 1, &request(flood_id, &[(10, NodeType::Client)]));
+        }
+
+        assert_eq!(floods.len(), MAX_FLOOD_TRACES);
+        assert!(floods.selected().is_some());
+    }
+}
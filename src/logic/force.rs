@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+
+use eframe::egui::{Pos2, Rect, Vec2};
+use wg_2024::network::NodeId;
+
+/// Barnes-Hut accuracy: cells seen under a smaller angle are approximated by their center
+/// of mass
+const THETA: f32 = 0.8;
+/// Depth at which coincident nodes stop splitting the quadtree
+const MAX_DEPTH: usize = 16;
+/// Ideal edge length, relative to the space available to each node
+const SPRING_LENGTH: f32 = 0.8;
+/// Pull towards the center keeping disconnected parts of the network together
+const GRAVITY: f32 = 0.05;
+/// Temperature decrease per step
+const COOLING: f32 = 0.97;
+/// Maximum move per step, in pixels, below which the layout is settled
+const SETTLED_TEMPERATURE: f32 = 0.5;
+/// Distance below which two nodes are considered at the same position
+const MIN_DISTANCE: f32 = 0.01;
+/// Angle spreading the directions coincident nodes are pushed in
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+/// Square region of the quadtree, with the total position and mass of its nodes
+#[derive(Clone, Copy, Debug)]
+struct Cell {
+    min: Pos2,
+    size: f32,
+    position_sum: Vec2,
+    mass: f32,
+    /// Index of the first of the four children, if the cell is split
+    children: Option<usize>,
+    /// The node in the cell, for leaves
+    body: Option<usize>,
+}
+
+impl Cell {
+    fn new(min: Pos2, size: f32) -> Self {
+        Self {
+            min,
+            size,
+            position_sum: Vec2::ZERO,
+            mass: 0.0,
+            children: None,
+            body: None,
+        }
+    }
+
+    fn quadrant(&self, position: Pos2) -> usize {
+        let half = self.size / 2.0;
+        usize::from(position.x >= self.min.x + half)
+            + 2 * usize::from(position.y >= self.min.y + half)
+    }
+}
+
+/// Quadtree approximating the repulsion of far away groups of nodes by their center of mass
+struct QuadTree {
+    cells: Vec<Cell>,
+}
+
+impl QuadTree {
+    fn build(positions: &[Pos2]) -> Self {
+        let bounds = Rect::from_points(positions);
+        let size = bounds.width().max(bounds.height()).max(1.0);
+        let mut tree = Self {
+            cells: vec![Cell::new(bounds.min, size)],
+        };
+        for body in 0..positions.len() {
+            tree.insert(0, body, positions, 0);
+        }
+        tree
+    }
+
+    fn insert(&mut self, cell: usize, body: usize, positions: &[Pos2], depth: usize) {
+        let position = positions[body];
+        self.cells[cell].position_sum += position.to_vec2();
+        self.cells[cell].mass += 1.0;
+
+        if let Some(first) = self.cells[cell].children {
+            let child = first + self.cells[cell].quadrant(position);
+            self.insert(child, body, positions, depth + 1);
+            return;
+        }
+
+        let Some(resident) = self.cells[cell].body else {
+            self.cells[cell].body = Some(body);
+            return;
+        };
+
+        // Coincident nodes share the leaf rather than splitting forever
+        if depth >= MAX_DEPTH {
+            return;
+        }
+
+        let Cell { min, size, .. } = self.cells[cell];
+        let half = size / 2.0;
+        let first = self.cells.len();
+        for (dx, dy) in [(0.0, 0.0), (half, 0.0), (0.0, half), (half, half)] {
+            self.cells.push(Cell::new(min + Vec2::new(dx, dy), half));
+        }
+        self.cells[cell].children = Some(first);
+        self.cells[cell].body = None;
+
+        for moved in [resident, body] {
+            let child = first + self.cells[cell].quadrant(positions[moved]);
+            self.insert(child, moved, positions, depth + 1);
+        }
+    }
+
+    /// Repulsion felt by `body` at `position`, for a repulsion of `k2 / distance` per node
+    fn repulsion(&self, body: usize, position: Pos2, k2: f32) -> Vec2 {
+        let mut force = Vec2::ZERO;
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let cell = &self.cells[index];
+            let (mut position_sum, mut mass) = (cell.position_sum, cell.mass);
+            if cell.children.is_none() && cell.body == Some(body) {
+                position_sum -= position.to_vec2();
+                mass -= 1.0;
+            }
+            if mass <= 0.0 {
+                continue;
+            }
+
+            let mut delta = position - (position_sum / mass).to_pos2();
+            if delta.length() < MIN_DISTANCE {
+                // Push coincident nodes apart, each in its own direction
+                #[allow(clippy::cast_precision_loss)]
+                let angle = body as f32 * GOLDEN_ANGLE;
+                delta = Vec2::angled(angle) * MIN_DISTANCE;
+            }
+            let distance = delta.length().max(MIN_DISTANCE);
+
+            match cell.children {
+                Some(first) if cell.size / distance >= THETA => stack.extend(first..first + 4),
+                _ => force += delta / distance * (k2 * mass / distance),
+            }
+        }
+
+        force
+    }
+}
+
+/// Fruchterman-Reingold simulation of the network, with Barnes-Hut repulsion, that can keep
+/// running as the topology changes
+#[derive(Clone, Debug)]
+pub struct ForceSimulation {
+    ids: Vec<NodeId>,
+    indices: HashMap<NodeId, usize>,
+    positions: Vec<Pos2>,
+    pinned: Vec<bool>,
+    edges: Vec<(usize, usize)>,
+    area: Rect,
+    temperature: f32,
+    displacements: Vec<Vec2>,
+}
+
+impl ForceSimulation {
+    /// An empty simulation keeping the nodes within `area`
+    #[must_use]
+    pub fn new(area: Rect) -> Self {
+        Self {
+            ids: Vec::new(),
+            indices: HashMap::new(),
+            positions: Vec::new(),
+            pinned: Vec::new(),
+            edges: Vec::new(),
+            area,
+            temperature: 0.0,
+            displacements: Vec::new(),
+        }
+    }
+
+    /// Replace the simulated graph. Nodes already simulated keep their position, unless
+    /// `reposition` is set, new ones start at the given position
+    pub fn set_graph(
+        &mut self,
+        nodes: &[(NodeId, Pos2)],
+        edges: &[(NodeId, NodeId)],
+        reposition: bool,
+    ) {
+        let previous: HashMap<NodeId, (Pos2, bool)> = self
+            .ids
+            .iter()
+            .zip(self.positions.iter().zip(&self.pinned))
+            .map(|(id, (position, pinned))| (*id, (*position, *pinned)))
+            .collect();
+
+        self.ids.clear();
+        self.indices.clear();
+        self.positions.clear();
+        self.pinned.clear();
+        for (id, position) in nodes {
+            let (position, pinned) = match previous.get(id) {
+                Some((kept, pinned)) if !reposition => (*kept, *pinned),
+                Some((_, pinned)) => (*position, *pinned),
+                None => (*position, false),
+            };
+            self.indices.insert(*id, self.ids.len());
+            self.ids.push(*id);
+            self.positions.push(self.area.clamp(position));
+            self.pinned.push(pinned);
+        }
+
+        self.edges = edges
+            .iter()
+            .filter_map(|(a, b)| Some((*self.indices.get(a)?, *self.indices.get(b)?)))
+            .collect();
+        self.displacements = vec![Vec2::ZERO; self.ids.len()];
+
+        self.reheat();
+    }
+
+    /// Keep the nodes within a new area
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+        for position in &mut self.positions {
+            *position = area.clamp(*position);
+        }
+        self.reheat();
+    }
+
+    /// Hold a node at `position` while the user drags it, or release it with `None`
+    pub fn pin(&mut self, id: NodeId, position: Option<Pos2>) {
+        let Some(index) = self.indices.get(&id).copied() else {
+            return;
+        };
+        self.pinned[index] = position.is_some();
+        if let Some(position) = position {
+            self.positions[index] = self.area.clamp(position);
+        }
+        self.reheat();
+    }
+
+    /// Move every node one step along the forces acting on it
+    #[allow(clippy::cast_precision_loss)]
+    pub fn step(&mut self) {
+        if self.ids.is_empty() {
+            self.temperature = 0.0;
+            return;
+        }
+
+        let k = SPRING_LENGTH * (self.area.area() / self.ids.len() as f32).sqrt();
+        let k2 = k * k;
+        let center = self.area.center();
+
+        // Repulsion between every pair of nodes, approximated for far away groups
+        let tree = QuadTree::build(&self.positions);
+        for (body, displacement) in self.displacements.iter_mut().enumerate() {
+            let position = self.positions[body];
+            *displacement = tree.repulsion(body, position, k2) + (center - position) * GRAVITY;
+        }
+
+        // Attraction along the links
+        for (a, b) in &self.edges {
+            let delta = self.positions[*a] - self.positions[*b];
+            let distance = delta.length().max(MIN_DISTANCE);
+            let force = delta / distance * (distance * distance / k);
+            self.displacements[*a] -= force;
+            self.displacements[*b] += force;
+        }
+
+        // Move by at most the temperature
+        for ((position, displacement), pinned) in self
+            .positions
+            .iter_mut()
+            .zip(&self.displacements)
+            .zip(&self.pinned)
+        {
+            let length = displacement.length();
+            if *pinned || length <= 0.0 {
+                continue;
+            }
+            *position = self
+                .area
+                .clamp(*position + *displacement / length * length.min(self.temperature));
+        }
+
+        self.temperature *= COOLING;
+    }
+
+    /// Step until the layout settles, or `max_steps` steps have been made
+    pub fn run(&mut self, max_steps: usize) {
+        for _ in 0..max_steps {
+            if self.is_settled() {
+                break;
+            }
+            self.step();
+        }
+    }
+
+    #[must_use]
+    pub fn is_settled(&self) -> bool {
+        self.temperature < SETTLED_TEMPERATURE
+    }
+
+    /// Current position of every node
+    pub fn positions(&self) -> impl Iterator<Item = (NodeId, Pos2)> + '_ {
+        self.ids.iter().copied().zip(self.positions.iter().copied())
+    }
+
+    /// Let the nodes move again, by up to a tenth of the area per step
+    fn reheat(&mut self) {
+        self.temperature = self.area.width().min(self.area.height()) / 10.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::pos2;
+
+    use super::*;
+
+    #[test]
+    fn keeps_the_nodes_within_the_area() {
+        let area = Rect::from_min_max(pos2(25.0, 25.0), pos2(400.0, 300.0));
+        // A star, with nodes starting on top of each other and outside the area
+        let nodes: Vec<(NodeId, Pos2)> = (0..40)
+            .map(|id| (id, pos2(f32::from(id % 4) * 300.0 - 200.0, 150.0)))
+            .collect();
+        let edges: Vec<(NodeId, NodeId)> = (1..40).map(|id| (0, id)).collect();
+
+        let mut simulation = ForceSimulation::new(area);
+        simulation.set_graph(&nodes, &edges, false);
+        assert!(simulation
+            .positions()
+            .all(|(_, position)| area.contains(position)));
+
+        for _ in 0..300 {
+            simulation.step();
+            assert!(simulation
+                .positions()
+                .all(|(_, position)| area.contains(position)));
+        }
+        assert!(simulation.is_settled());
+
+        // Shrinking the area pulls every node in
+        let smaller = Rect::from_min_max(pos2(25.0, 25.0), pos2(100.0, 100.0));
+        simulation.set_area(smaller);
+        simulation.step();
+        assert!(simulation
+            .positions()
+            .all(|(_, position)| smaller.contains(position)));
+    }
+}
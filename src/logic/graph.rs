@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::stable_graph::{NodeIndex, StableUnGraph};
+
+use wg_2024::network::NodeId;
+
+use crate::logic::error::GuiError;
+
+/// Undirected graph of the network topology, keyed by `NodeId`.
+///
+/// This is the only place links between nodes are stored.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkGraph {
+    graph: StableUnGraph<NodeId, ()>,
+    indices: HashMap<NodeId, NodeIndex>,
+    revision: u64,
+}
+
+impl NetworkGraph {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node if it is not part of the graph yet
+    pub fn add_node(&mut self, id: NodeId) -> NodeIndex {
+        if let Some(index) = self.indices.get(&id) {
+            return *index;
+        }
+        let index = self.graph.add_node(id);
+        self.indices.insert(id, index);
+        self.revision += 1;
+        index
+    }
+
+    /// Remove a node and all of its links, returning whether it was part of the graph
+    pub fn remove_node(&mut self, id: NodeId) -> bool {
+        match self.indices.remove(&id) {
+            Some(index) => {
+                self.graph.remove_node(index);
+                self.revision += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Link two nodes, unless they already are
+    ///
+    /// # Errors
+    /// Returns [`GuiError::UnknownNode`] if either node is not part of the graph.
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId) -> Result<(), GuiError> {
+        let (index_a, index_b) = (self.index(a)?, self.index(b)?);
+        if a != b && !self.graph.contains_edge(index_a, index_b) {
+            self.graph.add_edge(index_a, index_b, ());
+            self.revision += 1;
+        }
+        Ok(())
+    }
+
+    /// Remove the link between two nodes, returning whether they were linked
+    ///
+    /// # Errors
+    /// Returns [`GuiError::UnknownNode`] if either node is not part of the graph.
+    pub fn remove_edge(&mut self, a: NodeId, b: NodeId) -> Result<bool, GuiError> {
+        let (index_a, index_b) = (self.index(a)?, self.index(b)?);
+        let removed = self
+            .graph
+            .find_edge(index_a, index_b)
+            .and_then(|edge| self.graph.remove_edge(edge))
+            .is_some();
+        if removed {
+            self.revision += 1;
+        }
+        Ok(removed)
+    }
+
+    #[must_use]
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        self.indices.contains_key(&id)
+    }
+
+    #[must_use]
+    pub fn contains_edge(&self, a: NodeId, b: NodeId) -> bool {
+        match (self.indices.get(&a), self.indices.get(&b)) {
+            (Some(index_a), Some(index_b)) => self.graph.contains_edge(*index_a, *index_b),
+            _ => false,
+        }
+    }
+
+    /// Neighbors of a node, sorted by id
+    #[must_use]
+    pub fn neighbors(&self, id: NodeId) -> Vec<NodeId> {
+        let mut neighbors: Vec<NodeId> = self
+            .indices
+            .get(&id)
+            .map(|index| {
+                self.graph
+                    .neighbors(*index)
+                    .map(|neighbor| self.graph[neighbor])
+                    .collect()
+            })
+            .unwrap_or_default();
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    /// Every node of the graph, in no particular order
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.graph.node_weights().copied()
+    }
+
+    /// Every link of the graph, once per pair of nodes
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.graph.edge_indices().filter_map(|edge| {
+            let (a, b) = self.graph.edge_endpoints(edge)?;
+            Some((self.graph[a], self.graph[b]))
+        })
+    }
+
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    #[must_use]
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    /// The underlying petgraph graph, for layout algorithms
+    #[must_use]
+    pub fn graph(&self) -> &StableUnGraph<NodeId, ()> {
+        &self.graph
+    }
+
+    /// Counter increased by every change to the graph
+    #[must_use]
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn clear(&mut self) {
+        self.graph.clear();
+        self.indices.clear();
+        self.revision += 1;
+    }
+
+    /// Check that the id index matches the graph and that there are no self-loops or
+    /// duplicated links
+    ///
+    /// # Errors
+    /// Returns [`GuiError::InconsistentTopology`] describing the first problem found.
+    pub fn check(&self) -> Result<(), GuiError> {
+        if self.indices.len() != self.graph.node_count() {
+            return Err(GuiError::InconsistentTopology(format!(
+                "{} indexed ids for {} graph nodes",
+                self.indices.len(),
+                self.graph.node_count()
+            )));
+        }
+        for (id, index) in &self.indices {
+            if self.graph.node_weight(*index) != Some(id) {
+                return Err(GuiError::InconsistentTopology(format!(
+                    "node {id} is indexed at the wrong graph node"
+                )));
+            }
+        }
+
+        let mut seen = HashSet::with_capacity(self.graph.edge_count());
+        for (a, b) in self.edges() {
+            if a == b {
+                return Err(GuiError::InconsistentTopology(format!(
+                    "node {a} is linked to itself"
+                )));
+            }
+            if !seen.insert((a.min(b), a.max(b))) {
+                return Err(GuiError::InconsistentTopology(format!(
+                    "nodes {a} and {b} are linked more than once"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn index(&self, id: NodeId) -> Result<NodeIndex, GuiError> {
+        self.indices
+            .get(&id)
+            .copied()
+            .ok_or(GuiError::UnknownNode(id))
+    }
+}
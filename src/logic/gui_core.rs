@@ -0,0 +1,366 @@
+use std::path::Path;
+
+use crossbeam_channel::{Receiver, Sender};
+
+use wg_2024::network::NodeId;
+
+use messages::gui_commands::{GUICommands, GUIEvents};
+
+use crate::logic::{
+    actions,
+    error::GuiError,
+    handlers::{EventHandler, IngestionConfig},
+    journal::{load_script, ScriptRunner},
+    recorder::EventRecorder,
+    replay::Replay,
+    state::GUIState,
+};
+
+/// UI-free core of the GUI: the state, the event reducer and the command API.
+///
+/// It can be driven directly through crossbeam channels, without an `egui::Context`,
+/// which makes it usable from integration tests and command line tools.
+pub struct GuiCore {
+    pub state: GUIState,
+    event_handler: EventHandler,
+    replay: Option<Replay>,
+    script: Option<ScriptRunner>,
+}
+
+impl GuiCore {
+    #[must_use]
+    pub fn new(sender: Sender<GUICommands>, receiver: Receiver<GUIEvents>) -> Self {
+        Self::with_ingestion_config(sender, receiver, IngestionConfig::default())
+    }
+
+    #[must_use]
+    pub fn with_ingestion_config(
+        sender: Sender<GUICommands>,
+        receiver: Receiver<GUIEvents>,
+        config: IngestionConfig,
+    ) -> Self {
+        Self {
+            state: GUIState::new(sender, receiver),
+            event_handler: EventHandler::with_config(config),
+            replay: None,
+            script: None,
+        }
+    }
+
+    /// Seed the force-directed layout. Set before the topology arrives, even the first
+    /// layout starts from the seeded positions, so a run can be reproduced from start-up
+    #[must_use]
+    pub fn with_layout_seed(mut self, seed: u64) -> Self {
+        self.state.layout_seed = Some(seed);
+        self
+    }
+
+    /// Create a core fed by a recording instead of a Simulation Controller
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the recording cannot be loaded.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, GuiError> {
+        let (replay, sender, receiver) = Replay::load(path)?;
+        let mut core = Self::new(sender, receiver);
+        core.replay = Some(replay);
+        Ok(core)
+    }
+
+    #[must_use]
+    pub fn replay_state(&self) -> Option<&Replay> {
+        self.replay.as_ref()
+    }
+
+    pub fn replay_mut(&mut self) -> Option<&mut Replay> {
+        self.replay.as_mut()
+    }
+
+    /// Record every received event to `path` as JSON lines.
+    ///
+    /// If the topology is already known, a snapshot of it is recorded first so that the
+    /// recording can be replayed on its own.
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the file cannot be created or written.
+    pub fn start_recording(&mut self, path: impl AsRef<Path>) -> Result<(), GuiError> {
+        let mut recorder = EventRecorder::create(path)?;
+        if self.state.initialized {
+            recorder.record(&actions::snapshot(&self.state))?;
+        }
+        self.event_handler.start_recording(recorder);
+        Ok(())
+    }
+
+    /// # Errors
+    /// Returns [`GuiError::File`] if the recording cannot be flushed.
+    pub fn stop_recording(&mut self) -> Result<(), GuiError> {
+        self.event_handler.stop_recording()
+    }
+
+    #[must_use]
+    pub fn recorder(&self) -> Option<&EventRecorder> {
+        self.event_handler.recorder()
+    }
+
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.state.initialized
+    }
+
+    /// Process one frame worth of incoming events, honoring the ingestion limits.
+    ///
+    /// Before the topology is received only `GUIEvents::Topology` is accepted.
+    pub fn pump(&mut self) -> usize {
+        if let Some(replay) = &mut self.replay {
+            replay.feed();
+        }
+
+        if self.state.initialized {
+            self.run_due_commands();
+            let processed = self.event_handler.handle_events(&mut self.state);
+            self.state.traffic.prune();
+            self.state.metrics.roll(self.state.nodes.len());
+            self.state.expire_pending_commands();
+            processed
+        } else {
+            self.event_handler.handle_initialization(&mut self.state);
+            usize::from(self.state.initialized)
+        }
+    }
+
+    /// Re-issue the commands of a script exported from the journal, at the time they were
+    /// issued since the start of the session. The script clock starts once the topology is
+    /// known.
+    ///
+    /// Returns the number of commands in the script.
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the script cannot be loaded.
+    pub fn run_script(&mut self, path: impl AsRef<Path>) -> Result<usize, GuiError> {
+        let runner = ScriptRunner::new(load_script(path)?);
+        let len = runner.len();
+        self.script = Some(runner);
+        Ok(len)
+    }
+
+    #[must_use]
+    pub fn script_state(&self) -> Option<&ScriptRunner> {
+        self.script.as_ref()
+    }
+
+    /// Issue the script commands whose time has come
+    fn run_due_commands(&mut self) {
+        let Some(script) = &mut self.script else {
+            return;
+        };
+
+        for command in script.due() {
+            let result = actions::execute(&mut self.state, command);
+            self.state.report(result);
+        }
+
+        if script.is_finished() {
+            self.script = None;
+        }
+    }
+
+    /// Process every event queued at the time of the call.
+    pub fn drain(&mut self) -> usize {
+        self.event_handler.drain(&mut self.state)
+    }
+
+    /// Apply a single event directly, bypassing the channel.
+    ///
+    /// # Errors
+    /// Returns the [`GuiError`] raised by the event, which is not recorded in the error panel.
+    pub fn apply(&mut self, event: GUIEvents) -> Result<(), GuiError> {
+        EventHandler::apply_event(&mut self.state, event)
+    }
+
+    /// # Errors
+    /// See [`actions::crash`].
+    pub fn crash(&mut self, drone: NodeId) -> Result<(), GuiError> {
+        actions::crash(&mut self.state, drone)
+    }
+
+    /// # Errors
+    /// See [`actions::set_pdr`].
+    pub fn set_pdr(&mut self, drone: NodeId, pdr: f32) -> Result<(), GuiError> {
+        actions::set_pdr(&mut self.state, drone, pdr)
+    }
+
+    /// # Errors
+    /// See [`actions::spawn`].
+    pub fn spawn(&mut self, id: NodeId, neighbors: &Vec<NodeId>, pdr: f32) -> Result<(), GuiError> {
+        actions::spawn(&mut self.state, id, neighbors, pdr)
+    }
+
+    /// # Errors
+    /// See [`actions::add_sender`].
+    pub fn add_sender(&mut self, node_id: NodeId, to_add: NodeId) -> Result<(), GuiError> {
+        actions::add_sender(&mut self.state, node_id, to_add)
+    }
+
+    /// # Errors
+    /// See [`actions::remove_sender`].
+    pub fn remove_sender(&mut self, node_id: NodeId, to_remove: NodeId) -> Result<(), GuiError> {
+        actions::remove_sender(&mut self.state, node_id, to_remove)
+    }
+
+    /// # Errors
+    /// See [`actions::crash_selected`].
+    pub fn crash_selected(&mut self) -> Result<(), GuiError> {
+        actions::crash_selected(&mut self.state)
+    }
+
+    /// # Errors
+    /// See [`actions::set_pdr_selected`].
+    pub fn set_pdr_selected(&mut self, pdr: f32) -> Result<(), GuiError> {
+        actions::set_pdr_selected(&mut self.state, pdr)
+    }
+
+    /// # Errors
+    /// See [`actions::remove_links_between_groups`].
+    pub fn remove_links_between_groups(&mut self) -> Result<(), GuiError> {
+        actions::remove_links_between_groups(&mut self.state)
+    }
+
+    /// # Errors
+    /// See [`actions::send_message`].
+    pub fn send_message(&mut self, src: NodeId, dest: NodeId, msg: &str) -> Result<(), GuiError> {
+        actions::send_message(&mut self.state, src, dest, msg)
+    }
+
+    /// # Errors
+    /// See [`actions::register`].
+    pub fn register(&mut self, client: NodeId, server: NodeId) -> Result<(), GuiError> {
+        actions::register(&mut self.state, client, server)
+    }
+
+    /// # Errors
+    /// See [`actions::get_list`].
+    pub fn get_client_list(&mut self, client: NodeId) -> Result<(), GuiError> {
+        actions::get_list(&mut self.state, client)
+    }
+
+    /// # Errors
+    /// See [`actions::logout`].
+    pub fn logout(&mut self, client: NodeId, server: NodeId) -> Result<(), GuiError> {
+        actions::logout(&mut self.state, client, server)
+    }
+
+    /// # Errors
+    /// See [`actions::ask_for_file_list`].
+    pub fn ask_for_file_list(&mut self, client: NodeId, server: NodeId) -> Result<(), GuiError> {
+        actions::ask_for_file_list(&mut self.state, client, server)
+    }
+
+    /// # Errors
+    /// See [`actions::get_file`].
+    pub fn get_file(
+        &mut self,
+        client: NodeId,
+        server: NodeId,
+        title: &str,
+    ) -> Result<(), GuiError> {
+        actions::get_file(&mut self.state, client, server, title)
+    }
+
+    /// # Errors
+    /// See [`actions::undo`].
+    pub fn undo(&mut self) -> Result<(), GuiError> {
+        actions::undo(&mut self.state)
+    }
+
+    /// # Errors
+    /// See [`actions::redo`].
+    pub fn redo(&mut self) -> Result<(), GuiError> {
+        actions::redo(&mut self.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crossbeam_channel::unbounded;
+
+    use super::*;
+    use crate::logic::{fixtures::topology, journal::CommandOutcome};
+
+    #[test]
+    fn commands_and_events_round_trip_over_the_channels() {
+        let (command_sender, commands) = unbounded();
+        let (events, event_receiver) = unbounded();
+        let mut core = GuiCore::new(command_sender, event_receiver);
+
+        // Only the topology is accepted before initialization
+        events.send(GUIEvents::Crash(1)).unwrap();
+        events.send(topology()).unwrap();
+        core.pump();
+        assert!(!core.is_initialized());
+        assert!(matches!(
+            core.state.errors.as_slice(),
+            [record] if matches!(record.error, GuiError::UnexpectedEvent(_))
+        ));
+        core.pump();
+        assert!(core.is_initialized());
+        assert_eq!(core.state.nodes.len(), 4);
+
+        core.set_pdr(1, 0.5).unwrap();
+        core.remove_sender(1, 2).unwrap();
+        assert!(matches!(
+            commands.try_recv(),
+            Ok(GUICommands::SetPDR(1, pdr)) if (pdr - 0.5).abs() < f32::EPSILON
+        ));
+        assert!(matches!(
+            commands.try_recv(),
+            Ok(GUICommands::RemoveSender(1, 2))
+        ));
+        assert_eq!(core.state.pending.commands().len(), 2);
+        // There is no event for a new PDR, it is committed once sent
+        assert!((core.state.nodes[&1].pdr - 0.5).abs() < f32::EPSILON);
+
+        events.send(GUIEvents::RemoveSender(2, 1)).unwrap();
+        assert_eq!(core.pump(), 1);
+        assert!(!core.state.graph.neighbors(1).contains(&2));
+        let outcomes: Vec<&CommandOutcome> = core
+            .state
+            .journal
+            .entries()
+            .iter()
+            .map(|entry| &entry.outcome)
+            .collect();
+        assert_eq!(
+            outcomes,
+            [&CommandOutcome::Sent, &CommandOutcome::Confirmed]
+        );
+
+        // The PDR expires as unconfirmed, not as a failure
+        core.state.pending.timeout = Duration::ZERO;
+        core.pump();
+        assert!(core.state.pending.is_empty());
+        assert_eq!(
+            core.state.journal.entries()[0].outcome,
+            CommandOutcome::Unconfirmed
+        );
+        assert_eq!(core.state.nodes[&1].drone_params.unconfirmed_pdr, Some(0.5));
+        assert!((core.state.nodes[&1].pdr - 0.5).abs() < f32::EPSILON);
+        assert_eq!(core.state.errors.len(), 1);
+        core.state.check_consistency().unwrap();
+
+        // A dropped controller is reported once
+        drop(events);
+        core.pump();
+        core.pump();
+        assert!(core.state.disconnected);
+        assert_eq!(
+            core.state
+                .errors
+                .iter()
+                .filter(|record| record.error == GuiError::ChannelDisconnected)
+                .count(),
+            1
+        );
+    }
+}
@@ -1,24 +1,58 @@
-use std::time::Instant;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use crossbeam_channel::TryRecvError;
 
 use colored::Colorize;
-use eframe::egui::{self, Color32};
-use log::{error, info};
+use log::info;
 
 use messages::gui_commands::GUIEvents;
-use rand::Rng;
 use wg_2024::{
     config::Drone as ConfigDrone,
+    network::NodeId,
     packet::{NodeType, PacketType},
 };
 
 use crate::{
-    constants::{HEIGHT, NODE_RADIUS, WIDTH},
-    logic::{actions::topology, nodes::NodeGUI, state::GUIState},
+    constants::{EVENT_COALESCE_THRESHOLD, MAX_EVENTS_PER_FRAME, MAX_EVENT_PROCESSING_MS},
+    logic::{
+        actions::topology,
+        error::GuiError,
+        layout,
+        nodes::NodeGUI,
+        recorder::EventRecorder,
+        state::{GUIState, IngestionStats},
+        traffic::PacketKind,
+    },
 };
 
-pub struct EventHandler;
+/// Limits applied when draining the `GUIEvents` channel each frame
+#[derive(Clone, Copy, Debug)]
+pub struct IngestionConfig {
+    /// Maximum number of events applied in a single frame
+    pub max_events_per_frame: usize,
+    /// Maximum time spent applying events in a single frame
+    pub max_frame_time: Duration,
+    /// Backlog size above which animation-only events are coalesced
+    pub coalesce_threshold: usize,
+}
+
+impl Default for IngestionConfig {
+    fn default() -> Self {
+        Self {
+            max_events_per_frame: MAX_EVENTS_PER_FRAME,
+            max_frame_time: Duration::from_millis(MAX_EVENT_PROCESSING_MS),
+            coalesce_threshold: EVENT_COALESCE_THRESHOLD,
+        }
+    }
+}
+
+pub struct EventHandler {
+    config: IngestionConfig,
+    recorder: Option<EventRecorder>,
+}
 
 impl Default for EventHandler {
     fn default() -> Self {
@@ -29,50 +63,266 @@ impl Default for EventHandler {
 impl EventHandler {
     #[must_use]
     pub fn new() -> Self {
-        Self
+        Self::with_config(IngestionConfig::default())
+    }
+
+    #[must_use]
+    pub fn with_config(config: IngestionConfig) -> Self {
+        Self {
+            config,
+            recorder: None,
+        }
+    }
+
+    #[must_use]
+    pub fn config(&self) -> &IngestionConfig {
+        &self.config
     }
 
-    pub fn handle_events(&self, state: &mut GUIState, ctx: &egui::Context) {
-        match state.receiver.try_recv() {
-            Ok(event) => Self::process_event(state, event, ctx),
-            Err(TryRecvError::Empty) => (),
+    /// Record every event received from now on, replacing any running recording
+    pub fn start_recording(&mut self, recorder: EventRecorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Stop the running recording, flushing it to disk
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the recording cannot be flushed.
+    pub fn stop_recording(&mut self) -> Result<(), GuiError> {
+        match self.recorder.take() {
+            Some(mut recorder) => recorder.flush(),
+            None => Ok(()),
+        }
+    }
+
+    #[must_use]
+    pub fn recorder(&self) -> Option<&EventRecorder> {
+        self.recorder.as_ref()
+    }
+
+    /// Receive the next event, recording it if a recording is running.
+    ///
+    /// A disconnected channel is reported once, then never polled again.
+    fn receive(&mut self, state: &mut GUIState) -> Result<GUIEvents, TryRecvError> {
+        if state.disconnected {
+            return Err(TryRecvError::Disconnected);
+        }
+        let event = match state.receiver.try_recv() {
+            Ok(event) => event,
             Err(TryRecvError::Disconnected) => {
-                error!(
-                    "[ {} ]: GUICommands receiver channel disconnected",
-                    "GUI".red()
-                );
+                state.disconnected = true;
+                state.record_error(GuiError::ChannelDisconnected);
+                return Err(TryRecvError::Disconnected);
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record(&event) {
+                // Stop recording instead of reporting the same failure for every event
+                state.record_error(e);
+                self.recorder = None;
             }
         }
+
+        Ok(event)
     }
 
-    pub fn handle_initialization(&self, state: &mut GUIState, ctx: &egui::Context) {
-        match state.receiver.try_recv() {
-            Ok(event) => {
-                if let GUIEvents::Topology(_, _, _) = event {
-                    Self::process_event(state, event, ctx);
-                } else {
-                    error!(
-                        "[ {} ] Received NON-Topology GUIEvent before Initialization",
-                        "GUI".red()
-                    );
+    /// Drain the pending `GUIEvents` for this frame.
+    ///
+    /// Events are applied in order until the per-frame budget or time cap is reached.
+    /// When the backlog exceeds the coalesce threshold the whole queued backlog is drained
+    /// instead, still within the time cap: only the latest animation event of each node is
+    /// kept, and the kept ones are applied before the next topology-changing event so they
+    /// never land on a link or node removed after them.
+    ///
+    /// Returns the number of events applied.
+    pub fn handle_events(&mut self, state: &mut GUIState) -> usize {
+        let started = Instant::now();
+        let backlog = state.receiver.len();
+        let saturated = backlog > self.config.coalesce_threshold;
+
+        let mut received = 0;
+        let mut processed = 0;
+        let mut coalesced = 0;
+        let mut animations: HashMap<NodeId, GUIEvents> = HashMap::new();
+
+        loop {
+            let budget_spent = if saturated {
+                received >= backlog
+            } else {
+                processed >= self.config.max_events_per_frame
+            };
+            if budget_spent || started.elapsed() >= self.config.max_frame_time {
+                break;
+            }
+
+            match self.receive(state) {
+                Ok(event) => {
+                    received += 1;
+                    match Self::animation_source(&event) {
+                        Some(src) if saturated => {
+                            if let Some(replaced) = animations.insert(src, event) {
+                                // Coalesced packets still count as traffic
+                                let result = Self::record_traffic(state, &replaced);
+                                state.report(result);
+                                coalesced += 1;
+                            }
+                        }
+                        _ => {
+                            processed += Self::flush_animations(state, &mut animations);
+                            let result = Self::apply_event(state, event);
+                            state.report(result);
+                            processed += 1;
+                        }
+                    }
                 }
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
             }
-            Err(TryRecvError::Empty) => (),
-            Err(TryRecvError::Disconnected) => {
-                eprintln!(
-                    "[ {} ]: GUICommands receiver channel disconnected during init",
-                    "Simulation Controller".red()
-                );
+        }
+        processed += Self::flush_animations(state, &mut animations);
+
+        state.ingestion = IngestionStats {
+            backlog: state.receiver.len(),
+            processed,
+            coalesced,
+        };
+
+        processed
+    }
+
+    /// Apply the coalesced animation events, returning how many there were
+    fn flush_animations(
+        state: &mut GUIState,
+        animations: &mut HashMap<NodeId, GUIEvents>,
+    ) -> usize {
+        let flushed = animations.len();
+        for (_, event) in animations.drain() {
+            let result = Self::apply_event(state, event);
+            state.report(result);
+        }
+        flushed
+    }
+
+    /// Apply every event queued at the time of the call, ignoring the per-frame limits.
+    ///
+    /// Returns the number of events applied.
+    pub fn drain(&mut self, state: &mut GUIState) -> usize {
+        let queued = state.receiver.len();
+        let mut processed = 0;
+        while processed < queued {
+            match self.receive(state) {
+                Ok(event) => {
+                    let result = Self::apply_event(state, event);
+                    state.report(result);
+                    processed += 1;
+                }
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+            }
+        }
+
+        state.ingestion = IngestionStats {
+            backlog: state.receiver.len(),
+            processed,
+            coalesced: 0,
+        };
+
+        processed
+    }
+
+    /// Source node of events that only drive animations and never change the topology
+    fn animation_source(event: &GUIEvents) -> Option<NodeId> {
+        match event {
+            GUIEvents::PacketSent(src, _, _) | GUIEvents::PacketDropped(src, _) => Some(*src),
+            _ => None,
+        }
+    }
+
+    /// Update the traffic statistics and the PDR checks with a packet event, once the
+    /// nodes it refers to are known
+    ///
+    /// # Errors
+    /// Returns [`GuiError::UnknownNode`] if the packet went through a node that does not
+    /// exist, without recording anything.
+    fn record_traffic(state: &mut GUIState, event: &GUIEvents) -> Result<(), GuiError> {
+        let nodes = match event {
+            GUIEvents::PacketSent(src, dest, _) => vec![*src, *dest],
+            GUIEvents::PacketDropped(src, _) => vec![*src],
+            _ => return Ok(()),
+        };
+        if let Some(unknown) = nodes.into_iter().find(|id| !state.nodes.contains_key(id)) {
+            return Err(GuiError::UnknownNode(unknown));
+        }
+
+        let (src, dropped, pack_type) = match event {
+            GUIEvents::PacketSent(src, dest, packet) => {
+                state.traffic.record_packet(*src, *dest, &packet.pack_type);
+                state.metrics.record_sent(PacketKind::of(&packet.pack_type));
+                state.routes.record_sent(*src, *dest, packet);
+                state.floods.record_sent(*src, *dest, packet);
+                (*src, false, &packet.pack_type)
             }
+            GUIEvents::PacketDropped(src, packet) => {
+                state.traffic.record_drop(*src);
+                state.metrics.record_drop();
+                state.routes.record_dropped(*src, packet);
+                (*src, true, &packet.pack_type)
+            }
+            _ => return Ok(()),
+        };
+
+        // Only drones drop packets, and only fragments. While a new PDR is pending the
+        // drone may still drop fragments in flight at the old rate, so they are not counted
+        if !matches!(pack_type, PacketType::MsgFragment(_)) || state.pending.pdr(src).is_some() {
+            return Ok(());
+        }
+        let Some(pdr) = state
+            .nodes
+            .get(&src)
+            .filter(|instance| instance.node_type == NodeType::Drone)
+            .map(|instance| instance.pdr)
+        else {
+            return Ok(());
+        };
+
+        if dropped {
+            state.pdr_checks.record_dropped(src, pdr);
+        } else {
+            state.pdr_checks.record_forwarded(src, pdr);
+        }
+        Ok(())
+    }
+
+    pub fn handle_initialization(&mut self, state: &mut GUIState) {
+        match self.receive(state) {
+            Ok(event) => {
+                let result = if let GUIEvents::Topology(_, _, _) = event {
+                    Self::apply_event(state, event)
+                } else {
+                    Err(GuiError::UnexpectedEvent(
+                        "Received NON-Topology GUIEvent before Initialization".to_string(),
+                    ))
+                };
+                state.report(result);
+            }
+            Err(TryRecvError::Empty | TryRecvError::Disconnected) => (),
         }
     }
 
+    /// Apply a single `GUIEvents` to the state.
+    ///
+    /// # Errors
+    /// Returns [`GuiError::UnknownNode`] if the event references a node that does not exist,
+    /// or [`GuiError::UnexpectedEvent`] if it spawns a node that already exists.
     #[allow(clippy::too_many_lines)]
-    fn process_event(state: &mut GUIState, event: GUIEvents, ctx: &egui::Context) {
+    pub fn apply_event(state: &mut GUIState, event: GUIEvents) -> Result<(), GuiError> {
+        state.confirm_command(&event);
+        Self::record_traffic(state, &event)?;
+
         match event {
             GUIEvents::Topology(drones, clients, servers) => {
                 info!("[ {} ]: Received Topology", "GUI".green());
-                topology(state, &drones, &clients, &servers);
+                topology(state, &drones, &clients, &servers)?;
             }
             GUIEvents::FileList(server, _, items) => {
                 info!(
@@ -88,39 +338,16 @@ impl EventHandler {
                     "GUI".green(),
                     client
                 );
-                state
-                    .nodes
-                    .get_mut(&client)
-                    .unwrap()
-                    .chat_params
-                    .client_list_value = Some(client_list);
+                Self::node_mut(state, client)?.chat_params.client_list_value = Some(client_list);
             }
-            GUIEvents::PacketSent(src, _, packet) => {
+            GUIEvents::PacketSent(src, dest, packet) => {
                 if state.show_animation {
-                    if let Some(node) = state.nodes.get_mut(&src) {
-                        if node.node_type == NodeType::Drone {
-                            match packet.pack_type {
-                                PacketType::MsgFragment(_) => node.color = Color32::BLUE,
-                                PacketType::Ack(_) => node.color = Color32::DARK_GREEN,
-                                PacketType::Nack(_) => node.color = Color32::DARK_RED,
-                                PacketType::FloodRequest(_) => node.color = Color32::WHITE,
-                                PacketType::FloodResponse(_) => node.color = Color32::DARK_GRAY,
-                            }
-                        }
-                    }
-                    state.nodes.get_mut(&src).unwrap().last_packet_time = Some(Instant::now());
-                    state.nodes.get_mut(&src).unwrap().pending_reset = true;
-                    ctx.request_repaint();
+                    state.animations.push_sent(src, dest, &packet);
                 }
             }
-            GUIEvents::PacketDropped(src, _) => {
+            GUIEvents::PacketDropped(src, packet) => {
                 if state.show_animation {
-                    if let Some(node) = state.nodes.get_mut(&src) {
-                        node.color = Color32::RED;
-                    }
-                    state.nodes.get_mut(&src).unwrap().last_packet_time = Some(Instant::now());
-                    state.nodes.get_mut(&src).unwrap().pending_reset = true;
-                    ctx.request_repaint();
+                    state.animations.push_dropped(src, &packet);
                 }
             }
             GUIEvents::MessageReceived(src, dest, msg) => {
@@ -132,35 +359,29 @@ impl EventHandler {
                     src
                 );
                 let formatted_msg = format!("[{src}] -> {msg}");
-                state
-                    .nodes
-                    .get_mut(&dest)
-                    .unwrap()
+                Self::node_mut(state, dest)?
                     .chat_params
                     .recv_message_client_value = Some(formatted_msg);
             }
             GUIEvents::RemoveSender(node_id, to_remove) => {
-                if let Some(edge) = state.edges.get_mut(&node_id) {
-                    if edge.0.contains(&to_remove) {
-                        edge.0.retain(|&node| node != to_remove);
-                    }
-                }
-
-                let instance = state.nodes.get_mut(&node_id).unwrap();
-
-                // Remove neighbor from the current instance.
-                instance.neighbor.retain(|&x| x != to_remove);
+                // The controller may echo both directions, the second one is a no-op
+                state.graph.remove_edge(node_id, to_remove)?;
             }
             GUIEvents::AddSender(node_id, to_add) => {
-                let neighbor = state.nodes.get_mut(&node_id).unwrap();
-                neighbor.neighbor.push(to_add);
-                let (vec, _) = state
-                    .edges
-                    .entry(node_id)
-                    .or_insert_with(|| (Vec::new(), Color32::GRAY));
-                vec.push(to_add);
+                state.graph.add_edge(node_id, to_add)?;
             }
             GUIEvents::Spawn(id, neighbors, pdr) => {
+                // Check the event first, so an invalid one does not replace a node or leave a
+                // half-linked one
+                if state.nodes.contains_key(&id) {
+                    return Err(GuiError::UnexpectedEvent(format!(
+                        "Spawn of node {id}, which already exists"
+                    )));
+                }
+                if let Some(unknown) = neighbors.iter().find(|n| !state.graph.contains_node(**n)) {
+                    return Err(GuiError::UnknownNode(*unknown));
+                }
+
                 state.spawn.id = None;
                 state.spawn.neighbors.clear();
                 state.spawn.pdr = None;
@@ -171,15 +392,16 @@ impl EventHandler {
                     pdr,
                 };
 
-                // add to nodes
-                let mut rng = rand::rng();
-                let (x, y) = (
-                    rng.random_range((100.0 + NODE_RADIUS)..(WIDTH - NODE_RADIUS)),
-                    rng.random_range((100.0 + NODE_RADIUS)..(HEIGHT - NODE_RADIUS)),
-                );
-                let new_drone = NodeGUI::new_drone(&drone, x, y);
+                // Place the drone next to its neighbors, where the user can spot it
+                let position = layout::free_position_near(state, &neighbors);
+                let new_drone = NodeGUI::new_drone(&drone, position.x, position.y);
 
                 state.nodes.insert(id, new_drone);
+                state.graph.add_node(id);
+                for neighbor in &neighbors {
+                    state.graph.add_edge(id, *neighbor)?;
+                }
+                state.spawned.insert(id, Instant::now());
 
                 info!(
                     "[ {} ] Successfully created Drone({}, {:?}, {})",
@@ -190,29 +412,141 @@ impl EventHandler {
                 );
             }
             GUIEvents::Crash(drone) => {
-                // remove from edge hashmap
-                state.edges.remove(&drone);
+                state
+                    .nodes
+                    .remove(&drone)
+                    .ok_or(GuiError::UnknownNode(drone))?;
 
-                let instance = state.nodes.get_mut(&drone).unwrap();
+                // Removing the node also removes all of its links
+                state.graph.remove_node(drone);
+                state.selection.remove(drone);
+                state.metrics.record_crash();
+            }
+        }
 
-                // remove edges starting from neighbor
-                for neighbor_id in &instance.neighbor {
-                    // get edges starting from neighbor
-                    if let Some(neighbor_drone) = state.edges.get_mut(neighbor_id) {
-                        neighbor_drone.0.retain(|x| *x != drone);
-                    }
-                }
+        Ok(())
+    }
 
-                let neighbors = state.nodes.get(&drone).unwrap().neighbor.clone();
-                let id = state.nodes.get(&drone).unwrap().id;
-                for node in neighbors {
-                    let a = state.nodes.get_mut(&node).unwrap();
-                    a.neighbor.retain(|&x| x != id);
-                }
+    fn node_mut(state: &mut GUIState, id: NodeId) -> Result<&mut NodeGUI, GuiError> {
+        state.nodes.get_mut(&id).ok_or(GuiError::UnknownNode(id))
+    }
+}
 
-                let id = state.nodes.get(&drone).unwrap().id;
-                state.nodes.remove(&id);
-            }
+#[cfg(test)]
+mod tests {
+    use crossbeam_channel::unbounded;
+    use wg_2024::{
+        network::SourceRoutingHeader,
+        packet::{Ack, Packet},
+    };
+
+    use super::*;
+    use crate::logic::fixtures::initialized_state;
+
+    fn ack(hops: &[NodeId]) -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: hops.to_vec(),
+            },
+            session_id: 0,
         }
     }
+
+    #[test]
+    fn the_graph_stays_consistent_after_spawn_and_crash() {
+        let mut state = initialized_state();
+        state.check_consistency().unwrap();
+
+        EventHandler::apply_event(&mut state, GUIEvents::Spawn(3, vec![1, 20], 0.2)).unwrap();
+        state.check_consistency().unwrap();
+        let mut neighbors = state.graph.neighbors(3);
+        neighbors.sort_unstable();
+        assert_eq!(neighbors, vec![1, 20]);
+        assert!(state.spawned.contains_key(&3));
+
+        // An unknown neighbor leaves no half-linked node behind
+        assert_eq!(
+            EventHandler::apply_event(&mut state, GUIEvents::Spawn(4, vec![1, 99], 0.2)),
+            Err(GuiError::UnknownNode(99))
+        );
+        assert!(!state.nodes.contains_key(&4));
+        state.check_consistency().unwrap();
+
+        EventHandler::apply_event(&mut state, GUIEvents::Crash(1)).unwrap();
+        state.check_consistency().unwrap();
+        assert!(!state.graph.contains_node(1));
+        assert_eq!(state.graph.neighbors(3), vec![20]);
+        assert_eq!(
+            EventHandler::apply_event(&mut state, GUIEvents::Crash(1)),
+            Err(GuiError::UnknownNode(1))
+        );
+    }
+
+    #[test]
+    fn a_spawn_of_an_existing_node_is_rejected() {
+        let mut state = initialized_state();
+
+        for id in [1, 10, 20] {
+            assert!(matches!(
+                EventHandler::apply_event(&mut state, GUIEvents::Spawn(id, vec![2], 0.2)),
+                Err(GuiError::UnexpectedEvent(_))
+            ));
+        }
+
+        assert_eq!(state.nodes[&10].node_type, NodeType::Client);
+        assert_eq!(state.nodes[&20].node_type, NodeType::Server);
+        assert_eq!(state.graph.neighbors(20), vec![2]);
+        state.check_consistency().unwrap();
+    }
+
+    #[test]
+    fn packets_through_unknown_nodes_are_not_recorded() {
+        let mut state = initialized_state();
+        let packet = ack(&[10, 1, 99]);
+
+        assert_eq!(
+            EventHandler::apply_event(&mut state, GUIEvents::PacketSent(1, 99, packet.clone())),
+            Err(GuiError::UnknownNode(99))
+        );
+        assert_eq!(
+            EventHandler::apply_event(&mut state, GUIEvents::PacketDropped(99, packet)),
+            Err(GuiError::UnknownNode(99))
+        );
+
+        assert!(state.traffic.node(1).is_none());
+        assert!(state.traffic.node(99).is_none());
+        assert!(state.routes.is_empty());
+        assert!(state.pdr_checks.get(99).is_none());
+    }
+
+    #[test]
+    fn coalesced_animations_are_applied_before_later_topology_events() {
+        let mut state = initialized_state();
+        let (events, receiver) = unbounded();
+        state.receiver = receiver;
+        state.show_animation = true;
+        let packet = ack(&[1, 2]);
+        for event in [
+            GUIEvents::PacketSent(1, 2, packet.clone()),
+            GUIEvents::PacketSent(1, 2, packet),
+            GUIEvents::RemoveSender(1, 2),
+            GUIEvents::Crash(1),
+        ] {
+            events.send(event).unwrap();
+        }
+
+        let mut handler = EventHandler::with_config(IngestionConfig {
+            coalesce_threshold: 0,
+            ..IngestionConfig::default()
+        });
+        handler.handle_events(&mut state);
+
+        // The kept packet was animated on the link before it was removed
+        assert_eq!(state.ingestion.coalesced, 1);
+        assert_eq!(state.ingestion.processed, 3);
+        assert_eq!(state.animations.len(), 1);
+        assert!(state.errors.is_empty());
+    }
 }
@@ -1,3 +1,3 @@
 pub mod event;
 
-pub use event::EventHandler;
+pub use event::{EventHandler, IngestionConfig};
@@ -0,0 +1,253 @@
+use std::fmt;
+
+use messages::gui_commands::GUICommands;
+
+use crate::constants::MAX_EDIT_HISTORY;
+
+/// A topology edit issued by the GUI, with the commands that revert it
+///
+/// A bulk action issues several commands that are undone and redone together.
+#[derive(Clone, Debug)]
+pub struct Edit {
+    pub commands: Vec<GUICommands>,
+    /// Commands reverting the edit, in the order they are issued. `None` for edits that
+    /// cannot be reverted, like `Crash` and `Spawn`
+    pub inverse: Option<Vec<GUICommands>>,
+}
+
+impl Edit {
+    #[must_use]
+    pub fn is_reversible(&self) -> bool {
+        self.inverse.is_some()
+    }
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.commands.as_slice() {
+            [] => write!(f, "Empty edit"),
+            [command] => write!(f, "GUICommand::{command:?}"),
+            [command, rest @ ..] => {
+                write!(f, "GUICommand::{command:?} and {} more", rest.len())
+            }
+        }
+    }
+}
+
+/// Undo/redo stacks of topology edits
+#[derive(Clone, Debug, Default)]
+pub struct EditHistory {
+    done: Vec<Edit>,
+    undone: Vec<Edit>,
+    // Edits recorded since `begin_group`, kept as a single edit by `end_group`
+    group: Option<Vec<(GUICommands, Option<GUICommands>)>>,
+    // Set while an undo or redo issues its command, so it is not recorded as a new edit
+    restoring: bool,
+}
+
+impl EditHistory {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an edit issued by the user, discarding the redo stack
+    pub fn record(&mut self, command: GUICommands, inverse: Option<GUICommands>) {
+        if self.restoring {
+            return;
+        }
+
+        if let Some(group) = &mut self.group {
+            group.push((command, inverse));
+            return;
+        }
+
+        self.push(Edit {
+            commands: vec![command],
+            inverse: inverse.map(|inverse| vec![inverse]),
+        });
+    }
+
+    /// Record the following edits as a single one, until [`Self::end_group`]
+    pub fn begin_group(&mut self) {
+        if self.group.is_none() {
+            self.group = Some(Vec::new());
+        }
+    }
+
+    /// Record the edits since [`Self::begin_group`] as a single edit, reverted by undoing
+    /// each of them in reverse order
+    pub fn end_group(&mut self) {
+        let Some(group) = self.group.take() else {
+            return;
+        };
+        if group.is_empty() {
+            return;
+        }
+
+        let (commands, inverse): (Vec<GUICommands>, Vec<Option<GUICommands>>) =
+            group.into_iter().unzip();
+        self.push(Edit {
+            commands,
+            inverse: inverse.into_iter().rev().collect(),
+        });
+    }
+
+    /// The edit the next undo would revert
+    #[must_use]
+    pub fn next_undo(&self) -> Option<&Edit> {
+        self.done.last()
+    }
+
+    /// The edit the next redo would issue again
+    #[must_use]
+    pub fn next_redo(&self) -> Option<&Edit> {
+        self.undone.last()
+    }
+
+    /// Edits that can be undone, oldest first
+    #[must_use]
+    pub fn done(&self) -> &[Edit] {
+        &self.done
+    }
+
+    /// Edits that can be redone, oldest undo first
+    #[must_use]
+    pub fn undone(&self) -> &[Edit] {
+        &self.undone
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+        self.group = None;
+    }
+
+    /// Drop the latest edit from the undo stack, for edits that cannot be reverted
+    pub fn discard_undo(&mut self) -> Option<Edit> {
+        self.done.pop()
+    }
+
+    /// Move the latest edit to the redo stack once its inverse was issued
+    pub fn mark_undone(&mut self) {
+        if let Some(edit) = self.done.pop() {
+            self.undone.push(edit);
+        }
+    }
+
+    /// Move the latest undone edit back to the undo stack once it was issued again
+    pub fn mark_redone(&mut self) {
+        if let Some(edit) = self.undone.pop() {
+            self.done.push(edit);
+        }
+    }
+
+    /// Stop (or resume) recording edits while an undo or redo issues its command
+    pub fn set_restoring(&mut self, restoring: bool) {
+        self.restoring = restoring;
+    }
+
+    fn push(&mut self, edit: Edit) {
+        if self.done.len() >= MAX_EDIT_HISTORY {
+            self.done.remove(0);
+        }
+        self.done.push(edit);
+        self.undone.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(a: u8, b: u8) -> (GUICommands, Option<GUICommands>) {
+        (
+            GUICommands::AddSender(a, b),
+            Some(GUICommands::RemoveSender(a, b)),
+        )
+    }
+
+    #[test]
+    fn undo_and_redo_move_edits_between_stacks() {
+        let mut history = EditHistory::new();
+        let (command, inverse) = link(1, 2);
+        history.record(command, inverse);
+        let (command, inverse) = link(1, 3);
+        history.record(command, inverse);
+
+        assert!(matches!(
+            history.next_undo().map(|edit| edit.commands.as_slice()),
+            Some([GUICommands::AddSender(1, 3)])
+        ));
+        history.mark_undone();
+        assert_eq!((history.done().len(), history.undone().len()), (1, 1));
+        assert!(matches!(
+            history.next_redo().map(|edit| edit.commands.as_slice()),
+            Some([GUICommands::AddSender(1, 3)])
+        ));
+
+        history.mark_redone();
+        assert_eq!((history.done().len(), history.undone().len()), (2, 0));
+
+        // A new edit discards the redo stack
+        history.mark_undone();
+        history.record(GUICommands::Crash(4), None);
+        assert!(history.next_redo().is_none());
+        assert!(!history.next_undo().is_some_and(Edit::is_reversible));
+    }
+
+    #[test]
+    fn edits_issued_while_restoring_are_not_recorded() {
+        let mut history = EditHistory::new();
+        history.set_restoring(true);
+        history.record(GUICommands::Crash(4), None);
+        history.set_restoring(false);
+
+        assert!(history.done().is_empty());
+    }
+
+    #[test]
+    fn a_group_is_a_single_edit_reverted_in_reverse_order() {
+        let mut history = EditHistory::new();
+        history.begin_group();
+        for (command, inverse) in [link(1, 2), link(1, 3)] {
+            history.record(command, inverse);
+        }
+        history.end_group();
+
+        assert_eq!(history.done().len(), 1);
+        let edit = &history.done()[0];
+        assert_eq!(edit.commands.len(), 2);
+        assert!(matches!(
+            edit.inverse.as_deref(),
+            Some([
+                GUICommands::RemoveSender(1, 3),
+                GUICommands::RemoveSender(1, 2)
+            ])
+        ));
+
+        // A single irreversible command makes the whole group irreversible
+        history.begin_group();
+        let (command, inverse) = link(2, 3);
+        history.record(command, inverse);
+        history.record(GUICommands::Crash(4), None);
+        history.end_group();
+        assert!(!history.next_undo().is_some_and(Edit::is_reversible));
+
+        // An empty group records nothing
+        history.begin_group();
+        history.end_group();
+        assert_eq!(history.done().len(), 2);
+    }
+
+    #[test]
+    fn keeps_a_bounded_number_of_edits() {
+        let mut history = EditHistory::new();
+        for _ in 0..=MAX_EDIT_HISTORY {
+            let (command, inverse) = link(1, 2);
+            history.record(command, inverse);
+        }
+
+        assert_eq!(history.done().len(), MAX_EDIT_HISTORY);
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+use messages::gui_commands::GUICommands;
+
+use crate::logic::error::GuiError;
+
+/// What happened to a command issued by the GUI
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandOutcome {
+    /// Delivered to the Simulation Controller
+    Sent,
+    /// Delivered and confirmed by a `GUIEvents`
+    Confirmed,
+    /// Delivered but not confirmed before the timeout
+    TimedOut,
+    /// Delivered, the Simulation Controller may not have an event to confirm it
+    Unconfirmed,
+    /// Could not be delivered to the Simulation Controller
+    Failed(String),
+}
+
+/// A command issued by the GUI
+#[derive(Clone, Debug)]
+pub struct JournalEntry {
+    pub elapsed_ms: u64,
+    pub command: GUICommands,
+    pub outcome: CommandOutcome,
+}
+
+/// A line of an exported command script
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScriptedCommand {
+    pub elapsed_ms: u64,
+    pub command: GUICommands,
+}
+
+/// Every command issued by the GUI, in order
+#[derive(Clone, Debug)]
+pub struct CommandJournal {
+    started: Instant,
+    entries: Vec<JournalEntry>,
+    // Entries removed by `clear`, so indices returned by `push` stay valid
+    cleared: usize,
+}
+
+impl Default for CommandJournal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandJournal {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            entries: Vec::new(),
+            cleared: 0,
+        }
+    }
+
+    /// Append a command and its outcome, returning the index of the entry
+    pub fn push(&mut self, command: GUICommands, outcome: CommandOutcome) -> usize {
+        let elapsed_ms = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.entries.push(JournalEntry {
+            elapsed_ms,
+            command,
+            outcome,
+        });
+        self.cleared + self.entries.len() - 1
+    }
+
+    /// Update the outcome of an entry once the command is confirmed or timed out.
+    /// Entries removed by [`CommandJournal::clear`] are ignored.
+    pub fn set_outcome(&mut self, index: usize, outcome: CommandOutcome) {
+        let Some(index) = index.checked_sub(self.cleared) else {
+            return;
+        };
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.outcome = outcome;
+        }
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    pub fn clear(&mut self) {
+        self.cleared += self.entries.len();
+        self.entries.clear();
+    }
+
+    /// Write the commands that reached the Simulation Controller as a JSON-lines script
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the script cannot be written.
+    pub fn export(&self, path: impl AsRef<Path>) -> Result<usize, GuiError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| GuiError::file(path, &e))?;
+        let mut writer = BufWriter::new(file);
+
+        let mut exported = 0;
+        for entry in self
+            .entries
+            .iter()
+            .filter(|entry| !matches!(entry.outcome, CommandOutcome::Failed(_)))
+        {
+            let line = ScriptedCommand {
+                elapsed_ms: entry.elapsed_ms,
+                command: entry.command.clone(),
+            };
+            serde_json::to_writer(&mut writer, &line).map_err(|e| GuiError::file(path, &e))?;
+            writer
+                .write_all(b"\n")
+                .map_err(|e| GuiError::file(path, &e))?;
+            exported += 1;
+        }
+
+        writer.flush().map_err(|e| GuiError::file(path, &e))?;
+        Ok(exported)
+    }
+}
+
+/// Read a script written by [`CommandJournal::export`]
+///
+/// # Errors
+/// Returns [`GuiError::File`] if the file cannot be read or a line is not a valid command.
+pub fn load_script(path: impl AsRef<Path>) -> Result<Vec<ScriptedCommand>, GuiError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| GuiError::file(path, &e))?;
+
+    let mut commands = Vec::new();
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| GuiError::file(path, &e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let command = serde_json::from_str(&line).map_err(|e| GuiError::File {
+            path: path.display().to_string(),
+            reason: format!("line {}: {e}", number + 1),
+        })?;
+        commands.push(command);
+    }
+
+    Ok(commands)
+}
+
+/// Re-issues the commands of a script with their original relative timing
+#[derive(Clone, Debug)]
+pub struct ScriptRunner {
+    commands: Vec<ScriptedCommand>,
+    position: usize,
+    started: Option<Instant>,
+}
+
+impl ScriptRunner {
+    #[must_use]
+    pub fn new(commands: Vec<ScriptedCommand>) -> Self {
+        Self {
+            commands,
+            position: 0,
+            started: None,
+        }
+    }
+
+    /// Commands whose time has come. The first call starts the script clock, which stands
+    /// for the start of the recorded session, so a first command issued late in the session
+    /// is issued just as late.
+    pub fn due(&mut self) -> Vec<GUICommands> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        let mut due = Vec::new();
+        while let Some(scripted) = self.commands.get(self.position) {
+            if scripted.elapsed_ms > elapsed_ms {
+                break;
+            }
+            due.push(scripted.command.clone());
+            self.position += 1;
+        }
+        due
+    }
+
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.commands.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exported_script_loads_back_without_failed_commands() {
+        let path = std::env::temp_dir().join(format!("gui-journal-{}.jsonl", std::process::id()));
+
+        let mut journal = CommandJournal::new();
+        let crash = journal.push(GUICommands::Crash(3), CommandOutcome::Sent);
+        journal.push(
+            GUICommands::AddSender(1, 2),
+            CommandOutcome::Failed("disconnected".to_string()),
+        );
+        journal.push(
+            GUICommands::SendMessageTo(10, 11, "hello  there".to_string()),
+            CommandOutcome::Sent,
+        );
+        journal.set_outcome(crash, CommandOutcome::Confirmed);
+        assert_eq!(journal.entries()[0].outcome, CommandOutcome::Confirmed);
+
+        assert_eq!(journal.export(&path).unwrap(), 2);
+        let script = load_script(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(script.len(), 2);
+        assert!(matches!(script[0].command, GUICommands::Crash(3)));
+        assert!(matches!(
+            script[1].command,
+            GUICommands::SendMessageTo(10, 11, ref text) if text == "hello  there"
+        ));
+
+        // Every command of a script recorded at once is due immediately
+        let mut runner = ScriptRunner::new(script);
+        assert_eq!(runner.due().len(), 2);
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn a_script_keeps_the_delay_before_its_first_command() {
+        let mut runner = ScriptRunner::new(vec![
+            ScriptedCommand {
+                elapsed_ms: 50,
+                command: GUICommands::Crash(3),
+            },
+            ScriptedCommand {
+                elapsed_ms: 60,
+                command: GUICommands::Crash(4),
+            },
+        ]);
+
+        assert!(runner.due().is_empty());
+        std::thread::sleep(std::time::Duration::from_millis(70));
+        assert_eq!(runner.due().len(), 2);
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn cleared_entries_keep_their_index() {
+        let mut journal = CommandJournal::new();
+        let first = journal.push(GUICommands::Crash(3), CommandOutcome::Sent);
+        journal.clear();
+        let second = journal.push(GUICommands::Crash(4), CommandOutcome::Sent);
+
+        journal.set_outcome(first, CommandOutcome::TimedOut);
+        journal.set_outcome(second, CommandOutcome::Confirmed);
+        assert_eq!(second, first + 1);
+        assert_eq!(journal.entries().len(), 1);
+        assert_eq!(journal.entries()[0].outcome, CommandOutcome::Confirmed);
+    }
+}
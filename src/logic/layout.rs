@@ -0,0 +1,436 @@
+use std::collections::{HashMap, VecDeque};
+
+use colored::Colorize;
+use eframe::egui::{pos2, Pos2, Rect, Vec2};
+use log::info;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use wg_2024::{network::NodeId, packet::NodeType};
+
+use crate::{
+    constants::{LAYOUT_MARGIN, LAYOUT_TRANSITION_SECS, NODE_RADIUS},
+    logic::{error::GuiError, live_layout::LiveLayout, nodes::NodeGUI, state::GUIState},
+};
+
+/// Positions tried around the neighbors of a spawned drone before giving up on avoiding
+/// overlaps
+const SPAWN_PLACEMENT_ATTEMPTS: usize = 200;
+/// Angle between two positions tried around the neighbors of a spawned drone
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+/// Algorithm placing the nodes on the canvas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// Force-directed placement, simulated live as the topology changes
+    ForceDirected,
+    /// Every node on a circle, sorted by id
+    Circle,
+    /// Clients, drones and servers in three bands from left to right
+    Layered,
+    /// Rows of nodes sorted by id
+    Grid,
+    /// Rows of nodes by hop distance from the root, children below their parent
+    ShortestPathTree(NodeId),
+    /// Positions loaded from a file
+    Saved,
+}
+
+impl Layout {
+    #[must_use]
+    pub fn name(&self) -> String {
+        match self {
+            Self::ForceDirected => "Force-directed".to_string(),
+            Self::Circle => "Circle".to_string(),
+            Self::Layered => "Layered by role".to_string(),
+            Self::Grid => "Grid".to_string(),
+            Self::ShortestPathTree(root) => format!("Shortest-path tree from {root}"),
+            Self::Saved => "Saved positions".to_string(),
+        }
+    }
+}
+
+/// Animated move of the nodes from their current positions to a new layout
+#[derive(Clone, Debug)]
+pub struct LayoutTransition {
+    start: HashMap<NodeId, (f32, f32)>,
+    target: HashMap<NodeId, (f32, f32)>,
+    progress: f32,
+}
+
+impl LayoutTransition {
+    #[must_use]
+    pub fn new(nodes: &HashMap<NodeId, NodeGUI>, target: HashMap<NodeId, (f32, f32)>) -> Self {
+        Self {
+            start: nodes
+                .iter()
+                .map(|(id, node)| (*id, (node.x, node.y)))
+                .collect(),
+            target,
+            progress: 0.0,
+        }
+    }
+
+    /// Follow the nodes moved to a canvas of size `to`, see [`rescale`]
+    pub fn rescale(&mut self, from: Vec2, to: Vec2, computed_on: Vec2) {
+        for position in self.start.values_mut().chain(self.target.values_mut()) {
+            *position = rescale(*position, from, to, computed_on);
+        }
+    }
+
+    /// Move the nodes `dt` seconds further, returning whether they reached the new layout
+    pub fn advance(&mut self, dt: f32, nodes: &mut HashMap<NodeId, NodeGUI>) -> bool {
+        self.progress = (self.progress + dt / LAYOUT_TRANSITION_SECS).min(1.0);
+        // Ease in and out
+        let t = self.progress * self.progress * (3.0 - 2.0 * self.progress);
+
+        for (id, (x, y)) in &self.target {
+            if let (Some(node), Some((start_x, start_y))) = (nodes.get_mut(id), self.start.get(id))
+            {
+                node.x = start_x + (x - start_x) * t;
+                node.y = start_y + (y - start_y) * t;
+            }
+        }
+
+        self.progress >= 1.0
+    }
+}
+
+/// Move the nodes to `layout`, animating the transition. The force-directed layout is
+/// simulated live on a background thread, starting from the positions of [`compute`]
+///
+/// # Errors
+/// Returns [`GuiError::UnknownNode`] if the root of a shortest-path tree is not part of the
+/// network, or [`GuiError::InvalidInput`] if there are no saved positions.
+pub fn apply(state: &mut GUIState, layout: Layout) -> Result<(), GuiError> {
+    let target = compute(state, layout)?;
+    // Without a seed the force-directed layout starts where the nodes are, there is
+    // nothing to animate before the live layout takes over
+    state.layout_transition = (layout != Layout::ForceDirected || state.layout_seed.is_some())
+        .then(|| LayoutTransition::new(&state.nodes, target));
+    state.live_layout = (layout == Layout::ForceDirected).then(LiveLayout::start);
+    state.layout = layout;
+    state.layout_canvas = state.canvas_size;
+
+    info!("[ {} ] Layout: {}", "GUI".green(), layout.name());
+    Ok(())
+}
+
+/// Advance the running layout transition by `dt` seconds, then follow the live layout
+pub fn animate(state: &mut GUIState, dt: f32) {
+    if let Some(transition) = &mut state.layout_transition {
+        if transition.advance(dt, &mut state.nodes) {
+            state.layout_transition = None;
+        }
+        return;
+    }
+
+    if let Some(live) = &mut state.live_layout {
+        live.sync(&state.graph, &state.nodes, state.canvas_size);
+        live.apply(&mut state.nodes);
+    }
+}
+
+/// Positions of every node under `layout`, within the canvas. The force-directed layout is
+/// not simulated here: it gives the positions its live simulation starts from, which are
+/// the same every time with a layout seed set, and the current positions otherwise
+///
+/// # Errors
+/// Returns [`GuiError::UnknownNode`] if the root of a shortest-path tree is not part of the
+/// network, or [`GuiError::InvalidInput`] if there are no saved positions.
+pub fn compute(state: &GUIState, layout: Layout) -> Result<HashMap<NodeId, (f32, f32)>, GuiError> {
+    let mut ids: Vec<NodeId> = state.nodes.keys().copied().collect();
+    ids.sort_unstable();
+    let area = area(state.canvas_size);
+
+    let mut positions = HashMap::with_capacity(ids.len());
+    match layout {
+        Layout::ForceDirected => match state.layout_seed {
+            Some(seed) => {
+                positions.extend(scatter(ids.iter().copied(), state.canvas_size, Some(seed)));
+            }
+            None => positions.extend(
+                ids.iter()
+                    .map(|id| (*id, (state.nodes[id].x, state.nodes[id].y))),
+            ),
+        },
+        Layout::Circle => circle(&ids, area, &mut positions),
+        Layout::Saved => {
+            let saved = state.saved_positions.as_ref().ok_or_else(|| {
+                GuiError::InvalidInput("No node positions have been loaded".to_string())
+            })?;
+            positions.extend(
+                saved
+                    .scaled(state.canvas_size)
+                    .into_iter()
+                    .filter(|(id, _)| state.nodes.contains_key(id)),
+            );
+        }
+        Layout::Layered => {
+            let band = area.width() / 3.0;
+            let roles = [NodeType::Client, NodeType::Drone, NodeType::Server];
+            for (i, role) in roles.iter().enumerate() {
+                let members: Vec<NodeId> = ids
+                    .iter()
+                    .copied()
+                    .filter(|id| state.nodes[id].node_type == *role)
+                    .collect();
+                #[allow(clippy::cast_precision_loss)]
+                let left = area.left() + band * i as f32;
+                let band_area =
+                    Rect::from_min_max(pos2(left, area.top()), pos2(left + band, area.bottom()));
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let per_column = ((area.height() / (NODE_RADIUS * 3.0)) as usize).max(1);
+                grid(
+                    &members,
+                    band_area,
+                    members.len().div_ceil(per_column),
+                    &mut positions,
+                );
+            }
+        }
+        Layout::Grid => {
+            #[allow(
+                clippy::cast_precision_loss,
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss
+            )]
+            let columns = (ids.len() as f32 * area.aspect_ratio()).sqrt().ceil() as usize;
+            grid(&ids, area, columns, &mut positions);
+        }
+        Layout::ShortestPathTree(root) => {
+            let levels = shortest_path_levels(state, root)?;
+            #[allow(clippy::cast_precision_loss)]
+            let row_height = area.height() / levels.len() as f32;
+            for (i, level) in levels.iter().enumerate() {
+                #[allow(clippy::cast_precision_loss)]
+                let top = area.top() + row_height * i as f32;
+                let row = Rect::from_min_max(
+                    pos2(area.left(), top),
+                    pos2(area.right(), top + row_height),
+                );
+                grid(level, row, level.len(), &mut positions);
+            }
+        }
+    }
+    Ok(positions)
+}
+
+/// A position close to the centroid of `neighbors`, or to the center of the canvas without
+/// any, that does not overlap other nodes
+#[must_use]
+pub fn free_position_near(state: &GUIState, neighbors: &[NodeId]) -> Pos2 {
+    let known: Vec<Pos2> = neighbors
+        .iter()
+        .filter_map(|id| state.nodes.get(id))
+        .map(NodeGUI::position)
+        .collect();
+    #[allow(clippy::cast_precision_loss)]
+    let center = if known.is_empty() {
+        area(state.canvas_size).center()
+    } else {
+        let sum = known
+            .iter()
+            .fold(Vec2::ZERO, |sum, position| sum + position.to_vec2());
+        (sum / known.len() as f32).to_pos2()
+    };
+
+    let is_free = |candidate: Pos2| {
+        state
+            .nodes
+            .values()
+            .all(|node| node.position().distance(candidate) >= NODE_RADIUS * 3.0)
+    };
+
+    // Walk a spiral out of the centroid until there is room
+    (0..SPAWN_PLACEMENT_ATTEMPTS)
+        .map(|step| {
+            #[allow(clippy::cast_precision_loss)]
+            let step = step as f32;
+            center + Vec2::angled(step * GOLDEN_ANGLE) * NODE_RADIUS * 1.5 * step.sqrt()
+        })
+        .find(|candidate| is_free(*candidate))
+        .unwrap_or(center)
+}
+
+/// Nodes scattered at random over a canvas of `size`, for the force-directed layout to
+/// start from. The same `seed` always scatters the same ids at the same positions
+#[must_use]
+pub fn scatter(
+    ids: impl Iterator<Item = NodeId>,
+    size: Vec2,
+    seed: Option<u64>,
+) -> HashMap<NodeId, (f32, f32)> {
+    let area = area(size);
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng()),
+    };
+
+    let mut ids: Vec<NodeId> = ids.collect();
+    ids.sort_unstable();
+    ids.into_iter()
+        .map(|id| {
+            (
+                id,
+                (
+                    rng.random_range(area.min.x..=area.max.x),
+                    rng.random_range(area.min.y..=area.max.y),
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Move a position from a canvas of size `from` to one of size `to`, for a layout computed
+/// on a canvas of size `computed_on`. The scale is the same on both axes, around the center
+/// of the canvas, so the layout keeps its shape. It is the ratio of how much the layout is
+/// scaled to fit each canvas, so resizing back to an earlier size restores the positions
+#[must_use]
+pub fn rescale((x, y): (f32, f32), from: Vec2, to: Vec2, computed_on: Vec2) -> (f32, f32) {
+    if from == to || from.min_elem() <= 0.0 || computed_on.min_elem() <= 0.0 {
+        return (x, y);
+    }
+    let scale = (to / computed_on).min_elem() / (from / computed_on).min_elem();
+    let (from_center, to_center) = (from / 2.0, to / 2.0);
+    (
+        to_center.x + (x - from_center.x) * scale,
+        to_center.y + (y - from_center.y) * scale,
+    )
+}
+
+/// Part of a canvas of `size` nodes are laid out in
+#[must_use]
+pub fn area(size: Vec2) -> Rect {
+    let max = size.max(Vec2::splat(2.0 * LAYOUT_MARGIN)) - Vec2::splat(LAYOUT_MARGIN);
+    Rect::from_min_max(pos2(LAYOUT_MARGIN, LAYOUT_MARGIN), max.to_pos2())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn circle(ids: &[NodeId], area: Rect, positions: &mut HashMap<NodeId, (f32, f32)>) {
+    let center = area.center();
+    let radius = area.width().min(area.height()) / 2.0;
+
+    for (i, id) in ids.iter().enumerate() {
+        // Start from the top, clockwise
+        let angle =
+            (i as f32) * std::f32::consts::TAU / (ids.len() as f32) - std::f32::consts::FRAC_PI_2;
+        positions.insert(
+            *id,
+            (
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            ),
+        );
+    }
+}
+
+/// Spread `ids` row by row over `columns` columns, each node centered in its cell
+#[allow(clippy::cast_precision_loss)]
+fn grid(ids: &[NodeId], area: Rect, columns: usize, positions: &mut HashMap<NodeId, (f32, f32)>) {
+    let columns = columns.max(1);
+    let rows = ids.len().div_ceil(columns).max(1);
+
+    for (i, id) in ids.iter().enumerate() {
+        let (column, row) = (i % columns, i / columns);
+        positions.insert(
+            *id,
+            (
+                area.left() + area.width() * (column as f32 + 0.5) / columns as f32,
+                area.top() + area.height() * (row as f32 + 0.5) / rows as f32,
+            ),
+        );
+    }
+}
+
+/// Nodes grouped by hop distance from `root`, in breadth-first order so that children follow
+/// their parent. Unreachable nodes are in the last level
+fn shortest_path_levels(state: &GUIState, root: NodeId) -> Result<Vec<Vec<NodeId>>, GuiError> {
+    if !state.graph.contains_node(root) {
+        return Err(GuiError::UnknownNode(root));
+    }
+
+    let mut depths = HashMap::from([(root, 0)]);
+    let mut levels = vec![vec![root]];
+    let mut queue = VecDeque::from([root]);
+    while let Some(node) = queue.pop_front() {
+        let depth = depths[&node] + 1;
+        for neighbor in state.graph.neighbors(node) {
+            if depths.contains_key(&neighbor) {
+                continue;
+            }
+            depths.insert(neighbor, depth);
+            if levels.len() <= depth {
+                levels.push(Vec::new());
+            }
+            levels[depth].push(neighbor);
+            queue.push_back(neighbor);
+        }
+    }
+
+    let mut unreachable: Vec<NodeId> = state
+        .nodes
+        .keys()
+        .copied()
+        .filter(|id| !depths.contains_key(id))
+        .collect();
+    if !unreachable.is_empty() {
+        unreachable.sort_unstable();
+        levels.push(unreachable);
+    }
+
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::fixtures::initialized_state;
+
+    fn assert_positions(state: &GUIState, expected: &HashMap<NodeId, (f32, f32)>) {
+        for (id, (x, y)) in expected {
+            let node = &state.nodes[id];
+            assert!(
+                (node.x - x).abs() < 1e-3 && (node.y - y).abs() < 1e-3,
+                "node {id} at ({}, {}) instead of ({x}, {y})",
+                node.x,
+                node.y
+            );
+        }
+    }
+
+    #[test]
+    fn resizing_back_restores_the_positions() {
+        let mut state = initialized_state();
+        let size = state.canvas_size;
+
+        for layout in [Layout::Circle, Layout::Layered, Layout::Grid] {
+            apply(&mut state, layout).unwrap();
+            animate(&mut state, LAYOUT_TRANSITION_SECS);
+            let expected = compute(&state, layout).unwrap();
+            assert_positions(&state, &expected);
+
+            for _ in 0..5 {
+                state.resize_canvas(Vec2::new(size.x / 2.0, size.y));
+                state.resize_canvas(Vec2::new(size.x, size.y * 3.0));
+                state.resize_canvas(size);
+            }
+            assert_positions(&state, &expected);
+        }
+    }
+
+    #[test]
+    fn a_transition_resized_back_ends_on_its_layout() {
+        let mut state = initialized_state();
+        let size = state.canvas_size;
+        let expected = compute(&state, Layout::Circle).unwrap();
+
+        apply(&mut state, Layout::Circle).unwrap();
+        animate(&mut state, LAYOUT_TRANSITION_SECS / 3.0);
+        state.resize_canvas(Vec2::new(size.x / 2.0, size.y));
+        animate(&mut state, LAYOUT_TRANSITION_SECS / 3.0);
+        state.resize_canvas(size);
+        animate(&mut state, LAYOUT_TRANSITION_SECS);
+
+        assert!(state.layout_transition.is_none());
+        assert_positions(&state, &expected);
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
+use eframe::egui::{Pos2, Rect, Vec2};
+use wg_2024::network::NodeId;
+
+use crate::logic::{force::ForceSimulation, graph::NetworkGraph, layout, nodes::NodeGUI};
+
+/// Pause between two steps of the layout thread
+const STEP_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Message from the GUI to the layout thread
+#[derive(Debug)]
+enum LayoutCommand {
+    /// Replace the graph. With `reposition`, every node takes the given position, otherwise
+    /// only new nodes do
+    Graph {
+        generation: u64,
+        nodes: Vec<(NodeId, Pos2)>,
+        edges: Vec<(NodeId, NodeId)>,
+        area: Rect,
+        reposition: bool,
+    },
+    /// Hold a node where the user drags it, or release it with `None`
+    Pin(NodeId, Option<Pos2>),
+}
+
+/// Positions computed by the layout thread for the graph of `generation`
+#[derive(Debug)]
+struct LayoutFrame {
+    generation: u64,
+    positions: Vec<(NodeId, Pos2)>,
+}
+
+/// Force-directed layout simulated on a background thread, following the topology as it
+/// changes. The thread stops when the handle is dropped
+#[derive(Clone, Debug)]
+pub struct LiveLayout {
+    commands: Sender<LayoutCommand>,
+    /// Latest frame computed by the thread, replaced by every step until the GUI takes it
+    frame: Arc<Mutex<Option<LayoutFrame>>>,
+    generation: u64,
+    /// Graph revision and canvas size last sent to the thread
+    synced: Option<(u64, Vec2)>,
+}
+
+impl LiveLayout {
+    /// Start the layout thread
+    ///
+    /// # Panics
+    /// Panics if the operating system cannot spawn the thread.
+    #[must_use]
+    pub fn start() -> Self {
+        let (commands, command_receiver) = crossbeam_channel::unbounded();
+        let frame = Arc::new(Mutex::new(None));
+
+        let latest = Arc::clone(&frame);
+        thread::Builder::new()
+            .name("live-layout".to_string())
+            .spawn(move || Self::run(&command_receiver, &latest))
+            .expect("failed to spawn the layout thread");
+
+        Self {
+            commands,
+            frame,
+            generation: 0,
+            synced: None,
+        }
+    }
+
+    /// Send the graph to the thread if it or the canvas changed since the last call
+    pub fn sync(
+        &mut self,
+        graph: &NetworkGraph,
+        nodes: &HashMap<NodeId, NodeGUI>,
+        canvas_size: Vec2,
+    ) {
+        let current = (graph.revision(), canvas_size);
+        if self.synced == Some(current) {
+            return;
+        }
+        // Positions were rescaled with the canvas, the thread must take them all
+        let reposition = self.synced.is_some_and(|(_, size)| size != canvas_size);
+        self.synced = Some(current);
+        self.generation += 1;
+
+        // Sorted so that the same graph is always simulated the same way
+        let mut graph_nodes: Vec<(NodeId, Pos2)> = graph
+            .nodes()
+            .filter_map(|id| Some((id, nodes.get(&id)?.position())))
+            .collect();
+        graph_nodes.sort_unstable_by_key(|(id, _)| *id);
+        let mut edges: Vec<(NodeId, NodeId)> = graph.edges().collect();
+        edges.sort_unstable();
+
+        let _ = self.commands.send(LayoutCommand::Graph {
+            generation: self.generation,
+            nodes: graph_nodes,
+            edges,
+            area: layout::area(canvas_size),
+            reposition,
+        });
+    }
+
+    /// Hold a node where the user drags it, or release it with `None`
+    pub fn pin(&self, id: NodeId, position: Option<Pos2>) {
+        let _ = self.commands.send(LayoutCommand::Pin(id, position));
+    }
+
+    /// Move the nodes to the latest positions computed for the current graph
+    pub fn apply(&self, nodes: &mut HashMap<NodeId, NodeGUI>) {
+        let Some(frame) = self
+            .frame
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .filter(|frame| frame.generation == self.generation)
+        else {
+            return;
+        };
+
+        for (id, position) in frame.positions {
+            if let Some(node) = nodes.get_mut(&id) {
+                node.x = position.x;
+                node.y = position.y;
+            }
+        }
+    }
+
+    fn run(commands: &Receiver<LayoutCommand>, latest: &Arc<Mutex<Option<LayoutFrame>>>) {
+        let mut simulation = ForceSimulation::new(Rect::ZERO);
+        let mut generation = 0;
+
+        loop {
+            // Sleep until something changes once the layout has settled
+            let first = if simulation.is_settled() {
+                match commands.recv() {
+                    Ok(command) => Some(command),
+                    Err(_) => return,
+                }
+            } else {
+                match commands.try_recv() {
+                    Ok(command) => Some(command),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => return,
+                }
+            };
+
+            for command in first.into_iter().chain(commands.try_iter()) {
+                match command {
+                    LayoutCommand::Graph {
+                        generation: new_generation,
+                        nodes,
+                        edges,
+                        area,
+                        reposition,
+                    } => {
+                        generation = new_generation;
+                        simulation.set_area(area);
+                        simulation.set_graph(&nodes, &edges, reposition);
+                    }
+                    LayoutCommand::Pin(id, position) => simulation.pin(id, position),
+                }
+            }
+
+            // Every handle was dropped, nobody takes the frames anymore
+            if Arc::strong_count(latest) == 1 {
+                return;
+            }
+
+            simulation.step();
+            let frame = LayoutFrame {
+                generation,
+                positions: simulation.positions().collect(),
+            };
+            *latest.lock().unwrap_or_else(PoisonError::into_inner) = Some(frame);
+
+            thread::sleep(STEP_INTERVAL);
+        }
+    }
+}
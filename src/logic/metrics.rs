@@ -0,0 +1,99 @@
+use std::{collections::VecDeque, time::Instant};
+
+use crate::{constants::METRICS_HISTORY_SECS, logic::traffic::PacketKind};
+
+/// Network activity during one second of the simulation
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetricsSample {
+    /// Seconds since the GUI started collecting metrics
+    pub second: u64,
+    pub sent: [u32; PacketKind::ALL.len()],
+    pub dropped: u32,
+    pub active_nodes: usize,
+    pub crashed_drones: usize,
+}
+
+impl MetricsSample {
+    #[must_use]
+    pub fn sent(&self, kind: PacketKind) -> u32 {
+        self.sent[kind.index()]
+    }
+}
+
+/// Per-second history of the network throughput, losses and size
+#[derive(Clone, Debug)]
+pub struct NetworkMetrics {
+    started: Instant,
+    current: MetricsSample,
+    history: VecDeque<MetricsSample>,
+    crashed_drones: usize,
+}
+
+impl Default for NetworkMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NetworkMetrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            current: MetricsSample::default(),
+            history: VecDeque::new(),
+            crashed_drones: 0,
+        }
+    }
+
+    pub fn record_sent(&mut self, kind: PacketKind) {
+        self.current.sent[kind.index()] += 1;
+    }
+
+    pub fn record_drop(&mut self) {
+        self.current.dropped += 1;
+    }
+
+    pub fn record_crash(&mut self) {
+        self.crashed_drones += 1;
+    }
+
+    /// Close the seconds that have passed, stamping them with the current network size
+    pub fn roll(&mut self, active_nodes: usize) {
+        self.current.active_nodes = active_nodes;
+        self.current.crashed_drones = self.crashed_drones;
+
+        let now = self.elapsed_secs();
+        while self.current.second < now {
+            let next = MetricsSample {
+                second: self.current.second + 1,
+                active_nodes,
+                crashed_drones: self.crashed_drones,
+                ..MetricsSample::default()
+            };
+            self.history
+                .push_back(std::mem::replace(&mut self.current, next));
+        }
+
+        while self.history.len() > METRICS_HISTORY_SECS {
+            self.history.pop_front();
+        }
+    }
+
+    /// Completed seconds, oldest first
+    #[must_use]
+    pub fn samples(&self) -> impl DoubleEndedIterator<Item = &MetricsSample> {
+        self.history.iter()
+    }
+
+    /// The second being collected
+    #[must_use]
+    pub fn current(&self) -> &MetricsSample {
+        &self.current
+    }
+
+    #[must_use]
+    pub fn elapsed_secs(&self) -> u64 {
+        self.started.elapsed().as_secs()
+    }
+}
@@ -1,4 +1,28 @@
 pub mod actions;
+pub mod animation;
+pub mod console;
+pub mod error;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod floods;
+pub mod force;
+pub mod graph;
+pub mod gui_core;
 pub mod handlers;
+pub mod history;
+pub mod journal;
+pub mod layout;
+pub mod live_layout;
+pub mod metrics;
 pub mod nodes;
+pub mod pdr;
+pub mod pending;
+pub mod positions;
+pub mod recorder;
+pub mod replay;
+pub mod routes;
+pub mod selection;
 pub mod state;
+pub mod traffic;
+
+pub use gui_core::GuiCore;
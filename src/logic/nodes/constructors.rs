@@ -14,7 +14,6 @@ impl NodeGUI {
     pub fn new_drone(drone: &ConfigDrone, x: f32, y: f32) -> Self {
         Self {
             id: drone.id,
-            neighbor: drone.connected_node_ids.clone(),
             pdr: drone.pdr,
             x,
             y,
@@ -30,9 +29,6 @@ impl NodeGUI {
             drone_params: DroneParams::new(),
             chat_params: ChatParam::new(),
             media_params: MediaParams::new(),
-
-            last_packet_time: None,
-            pending_reset: false,
         }
     }
 
@@ -51,7 +47,6 @@ impl NodeGUI {
 
         Self {
             id: client.id,
-            neighbor: client.connected_drone_ids.clone(),
             pdr: 0.0,
             x,
             y,
@@ -67,9 +62,6 @@ impl NodeGUI {
             drone_params: DroneParams::new(),
             chat_params: ChatParam::new(),
             media_params: MediaParams::new(),
-
-            last_packet_time: None,
-            pending_reset: false,
         }
     }
 
@@ -89,7 +81,6 @@ impl NodeGUI {
 
         Self {
             id: server.id,
-            neighbor: server.connected_drone_ids.clone(),
             pdr: 0.0,
             x,
             y,
@@ -105,9 +96,6 @@ impl NodeGUI {
             drone_params: DroneParams::new(),
             chat_params: ChatParam::new(),
             media_params: MediaParams::new(),
-
-            last_packet_time: None,
-            pending_reset: false,
         }
     }
 }
@@ -1,6 +1,5 @@
 use eframe::egui;
 use messages::high_level_messages::ServerType;
-use std::time::Instant;
 use wg_2024::{network::NodeId, packet::NodeType};
 
 use super::{ChatParam, DroneParams, MediaParams};
@@ -10,7 +9,6 @@ use crate::logic::nodes::types::ClientType;
 #[derive(Clone, Debug)]
 pub struct NodeGUI {
     pub id: NodeId,
-    pub neighbor: Vec<NodeId>,
     pub pdr: f32,
     pub x: f32,
     pub y: f32,
@@ -26,7 +24,31 @@ pub struct NodeGUI {
     pub drone_params: DroneParams,
     pub chat_params: ChatParam,
     pub media_params: MediaParams,
+}
+
+impl NodeGUI {
+    /// Position of the node in world coordinates
+    #[must_use]
+    pub fn position(&self) -> egui::Pos2 {
+        egui::pos2(self.x, self.y)
+    }
 
-    pub last_packet_time: Option<Instant>,
-    pub pending_reset: bool,
+    /// Kind of node, as shown to the user
+    #[must_use]
+    pub fn role(&self) -> &'static str {
+        match self.node_type {
+            NodeType::Drone => "Drone",
+            NodeType::Client => match self.client_type {
+                Some(ClientType::Chat) => "Chat client",
+                Some(ClientType::Media) => "Media client",
+                None => "Client",
+            },
+            NodeType::Server => match self.server_type {
+                Some(ServerType::Chat) => "Communication server",
+                Some(ServerType::Text) => "Text content server",
+                Some(ServerType::Media) => "Media content server",
+                None => "Server",
+            },
+        }
+    }
 }
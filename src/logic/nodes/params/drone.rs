@@ -3,6 +3,9 @@ pub struct DroneParams {
     pub crashed: bool,
     pub set_pdr: bool,
     pub pdr_value: Option<String>,
+    /// Latest PDR sent to the Simulation Controller once its pending command expired, shown
+    /// for information since no event acknowledges it
+    pub unconfirmed_pdr: Option<f32>,
 }
 
 impl DroneParams {
@@ -12,6 +15,7 @@ impl DroneParams {
             crashed: false,
             set_pdr: false,
             pdr_value: None,
+            unconfirmed_pdr: None,
         }
     }
 }
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use wg_2024::network::NodeId;
+
+use crate::constants::{PDR_CHECK_MIN_SAMPLES, PDR_CHECK_WINDOWS, PDR_CHECK_Z};
+
+/// Whether a drone drops packets at the rate it was configured with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PdrVerdict {
+    /// Too few fragments observed to tell
+    NotEnoughData,
+    /// The configured PDR lies within the confidence interval of the observed ratio
+    Consistent,
+    /// The configured PDR lies outside the confidence interval of the observed ratio
+    Inconsistent,
+}
+
+/// Fragments forwarded and dropped by a drone since its PDR was last set
+///
+/// The interval is checked every `PDR_CHECK_MIN_SAMPLES` fragments, and the verdict only
+/// flips after `PDR_CHECK_WINDOWS` consecutive checks disagree with it.
+#[derive(Clone, Copy, Debug)]
+pub struct PdrObservation {
+    pub configured: f32,
+    pub forwarded: u64,
+    pub dropped: u64,
+    inconsistent: bool,
+    // Consecutive checks that disagree with `inconsistent`
+    streak: u32,
+}
+
+impl PdrObservation {
+    fn new(configured: f32) -> Self {
+        Self {
+            configured,
+            forwarded: 0,
+            dropped: 0,
+            inconsistent: false,
+            streak: 0,
+        }
+    }
+
+    fn record(&mut self, dropped: bool) {
+        if dropped {
+            self.dropped += 1;
+        } else {
+            self.forwarded += 1;
+        }
+        if self.samples().is_multiple_of(PDR_CHECK_MIN_SAMPLES) {
+            self.check();
+        }
+    }
+
+    /// Compare the configured PDR with the interval at the end of a window
+    fn check(&mut self) {
+        let Some((low, high)) = self.confidence_interval() else {
+            return;
+        };
+        let inconsistent = !(low..=high).contains(&self.configured);
+
+        if inconsistent == self.inconsistent {
+            self.streak = 0;
+            return;
+        }
+        self.streak += 1;
+        if self.streak >= PDR_CHECK_WINDOWS {
+            self.inconsistent = inconsistent;
+            self.streak = 0;
+        }
+    }
+
+    /// Fragments that reached the drone
+    #[must_use]
+    pub fn samples(&self) -> u64 {
+        self.forwarded + self.dropped
+    }
+
+    /// Fraction of the fragments the drone dropped
+    #[must_use]
+    pub fn observed(&self) -> Option<f32> {
+        #[allow(clippy::cast_precision_loss)]
+        (self.samples() > 0).then(|| self.dropped as f32 / self.samples() as f32)
+    }
+
+    /// Wilson score interval of the observed drop ratio
+    #[must_use]
+    pub fn confidence_interval(&self) -> Option<(f32, f32)> {
+        if self.samples() == 0 {
+            return None;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let n = self.samples() as f32;
+        let p = self.observed()?;
+        let z2 = PDR_CHECK_Z * PDR_CHECK_Z;
+
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin = PDR_CHECK_Z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+        Some(((center - margin).max(0.0), (center + margin).min(1.0)))
+    }
+
+    #[must_use]
+    pub fn verdict(&self) -> PdrVerdict {
+        if self.samples() < PDR_CHECK_MIN_SAMPLES * u64::from(PDR_CHECK_WINDOWS) {
+            PdrVerdict::NotEnoughData
+        } else if self.inconsistent {
+            PdrVerdict::Inconsistent
+        } else {
+            PdrVerdict::Consistent
+        }
+    }
+}
+
+/// Observed drop ratio of every drone, compared to its configured PDR
+#[derive(Clone, Debug, Default)]
+pub struct PdrChecks {
+    drones: HashMap<NodeId, PdrObservation>,
+}
+
+impl PdrChecks {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a fragment forwarded by a drone whose last PDR sent is `configured`
+    pub fn record_forwarded(&mut self, drone: NodeId, configured: f32) {
+        self.observation(drone, configured).record(false);
+    }
+
+    /// Count a fragment dropped by a drone whose last PDR sent is `configured`
+    pub fn record_dropped(&mut self, drone: NodeId, configured: f32) {
+        self.observation(drone, configured).record(true);
+    }
+
+    #[must_use]
+    pub fn get(&self, drone: NodeId) -> Option<&PdrObservation> {
+        self.drones.get(&drone)
+    }
+
+    /// Drones whose behaviour is statistically inconsistent with their PDR
+    pub fn inconsistent(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.drones
+            .iter()
+            .filter(|(_, observation)| observation.verdict() == PdrVerdict::Inconsistent)
+            .map(|(drone, _)| *drone)
+    }
+
+    /// Start observing `drone` again, once its PDR was set to `configured`, so the
+    /// fragments dropped at the old and new rates are not mixed
+    pub fn restart(&mut self, drone: NodeId, configured: f32) {
+        self.drones.insert(drone, PdrObservation::new(configured));
+    }
+
+    pub fn clear(&mut self) {
+        self.drones.clear();
+    }
+
+    /// The running observation of a drone, restarted whenever its PDR changes
+    fn observation(&mut self, drone: NodeId, configured: f32) -> &mut PdrObservation {
+        let observation = self
+            .drones
+            .entry(drone)
+            .or_insert_with(|| PdrObservation::new(configured));
+        if (observation.configured - configured).abs() > f32::EPSILON {
+            *observation = PdrObservation::new(configured);
+        }
+        observation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOWS_SAMPLES: u64 = PDR_CHECK_MIN_SAMPLES * PDR_CHECK_WINDOWS as u64;
+
+    #[test]
+    fn a_drone_dropping_as_configured_is_consistent() {
+        let mut checks = PdrChecks::new();
+        for i in 0..WINDOWS_SAMPLES {
+            if i % 2 == 0 {
+                checks.record_dropped(1, 0.5);
+            } else {
+                checks.record_forwarded(1, 0.5);
+            }
+        }
+
+        let observation = checks.get(1).copied().unwrap();
+        assert_eq!(observation.samples(), WINDOWS_SAMPLES);
+        assert_eq!(observation.observed(), Some(0.5));
+        let (low, high) = observation.confidence_interval().unwrap();
+        assert!(low < 0.5 && 0.5 < high);
+        assert_eq!(observation.verdict(), PdrVerdict::Consistent);
+        assert_eq!(checks.inconsistent().count(), 0);
+    }
+
+    #[test]
+    fn the_verdict_flips_only_after_several_windows() {
+        let mut checks = PdrChecks::new();
+        for _ in 0..WINDOWS_SAMPLES - 1 {
+            checks.record_dropped(1, 0.0);
+        }
+        assert_eq!(checks.get(1).unwrap().verdict(), PdrVerdict::NotEnoughData);
+
+        checks.record_dropped(1, 0.0);
+        assert_eq!(checks.get(1).unwrap().verdict(), PdrVerdict::Inconsistent);
+        assert_eq!(checks.inconsistent().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn a_new_pdr_restarts_the_observation() {
+        let mut checks = PdrChecks::new();
+        for _ in 0..WINDOWS_SAMPLES {
+            checks.record_dropped(1, 0.0);
+        }
+        checks.record_dropped(1, 1.0);
+
+        let observation = checks.get(1).unwrap();
+        assert_eq!(observation.samples(), 1);
+        assert_eq!(observation.verdict(), PdrVerdict::NotEnoughData);
+
+        // Setting the same PDR again also starts over
+        checks.restart(1, 1.0);
+        assert_eq!(checks.get(1).unwrap().samples(), 0);
+    }
+}
@@ -0,0 +1,221 @@
+use std::time::{Duration, Instant};
+
+use wg_2024::network::NodeId;
+
+use messages::gui_commands::{GUICommands, GUIEvents};
+
+use crate::constants::COMMAND_TIMEOUT_MS;
+
+/// A command sent to the Simulation Controller and not confirmed yet
+#[derive(Clone, Debug)]
+pub struct PendingCommand {
+    pub command: GUICommands,
+    /// Index of the command in the [`CommandJournal`](crate::logic::journal::CommandJournal)
+    pub journal_index: usize,
+    pub sent_at: Instant,
+}
+
+/// Commands waiting for the `GUIEvents` that confirms them
+#[derive(Clone, Debug)]
+pub struct PendingCommands {
+    /// How long a command may wait before it is flagged as failed
+    pub timeout: Duration,
+    commands: Vec<PendingCommand>,
+}
+
+impl Default for PendingCommands {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PendingCommands {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_timeout(Duration::from_millis(COMMAND_TIMEOUT_MS))
+    }
+
+    #[must_use]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Whether the Simulation Controller always answers `command` with a `GUIEvents`.
+    ///
+    /// `RegisterTo`, `LogOut`, `GetFile` and `SetPDR` have no confirming event. They are
+    /// tracked anyway, and expire as unconfirmed rather than failed.
+    #[must_use]
+    pub fn expects_confirmation(command: &GUICommands) -> bool {
+        matches!(
+            command,
+            GUICommands::Crash(_)
+                | GUICommands::AddSender(_, _)
+                | GUICommands::RemoveSender(_, _)
+                | GUICommands::Spawn(_, _, _)
+                | GUICommands::SendMessageTo(_, _, _)
+                | GUICommands::GetClientList(_)
+                | GUICommands::AskForFileList(_, _)
+        )
+    }
+
+    /// Start waiting for the confirmation of a command that was just sent
+    pub fn track(&mut self, command: GUICommands, journal_index: usize) {
+        self.commands.push(PendingCommand {
+            command,
+            journal_index,
+            sent_at: Instant::now(),
+        });
+    }
+
+    /// Stop waiting for the oldest command confirmed by `event`, returning it
+    pub fn confirm(&mut self, event: &GUIEvents) -> Option<PendingCommand> {
+        let index = self
+            .commands
+            .iter()
+            .position(|pending| Self::confirms(event, &pending.command))?;
+        Some(self.commands.remove(index))
+    }
+
+    /// Stop waiting for the commands older than the timeout, returning them
+    pub fn expire(&mut self) -> Vec<PendingCommand> {
+        let timeout = self.timeout;
+        let (expired, pending) = self
+            .commands
+            .drain(..)
+            .partition(|pending| pending.sent_at.elapsed() >= timeout);
+        self.commands = pending;
+        expired
+    }
+
+    #[must_use]
+    pub fn commands(&self) -> &[PendingCommand] {
+        &self.commands
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Nodes affected by a pending `Crash` or `SetPDR`
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.commands
+            .iter()
+            .filter_map(|pending| match pending.command {
+                GUICommands::Crash(drone) | GUICommands::SetPDR(drone, _) => Some(drone),
+                _ => None,
+            })
+    }
+
+    /// Latest PDR sent to `drone` that is still pending
+    #[must_use]
+    pub fn pdr(&self, drone: NodeId) -> Option<f32> {
+        self.commands
+            .iter()
+            .rev()
+            .find_map(|pending| match pending.command {
+                GUICommands::SetPDR(id, pdr) if id == drone => Some(pdr),
+                _ => None,
+            })
+    }
+
+    /// Links affected by a pending `AddSender` or `RemoveSender`
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.commands
+            .iter()
+            .filter_map(|pending| match pending.command {
+                GUICommands::AddSender(a, b) | GUICommands::RemoveSender(a, b) => Some((a, b)),
+                _ => None,
+            })
+    }
+
+    fn confirms(event: &GUIEvents, command: &GUICommands) -> bool {
+        // Links are undirected, the controller may echo either direction
+        let same_link =
+            |a: NodeId, b: NodeId, x: NodeId, y: NodeId| (a, b) == (x, y) || (a, b) == (y, x);
+
+        match (event, command) {
+            (GUIEvents::Crash(a), GUICommands::Crash(b))
+            | (GUIEvents::Spawn(a, _, _), GUICommands::Spawn(b, _, _))
+            | (GUIEvents::ClientList(a, _), GUICommands::GetClientList(b)) => a == b,
+            (GUIEvents::AddSender(a, b), GUICommands::AddSender(x, y))
+            | (GUIEvents::RemoveSender(a, b), GUICommands::RemoveSender(x, y)) => {
+                same_link(*a, *b, *x, *y)
+            }
+            (
+                GUIEvents::MessageReceived(src, dest, msg),
+                GUICommands::SendMessageTo(x, y, text),
+            ) => (src, dest, msg) == (x, y, text),
+            (GUIEvents::FileList(server, client, _), GUICommands::AskForFileList(x, y)) => {
+                (client, server) == (x, y)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirms_the_oldest_matching_command() {
+        let mut pending = PendingCommands::new();
+        pending.track(GUICommands::AddSender(1, 2), 0);
+        pending.track(GUICommands::Crash(3), 1);
+        pending.track(GUICommands::AddSender(1, 2), 2);
+
+        assert!(pending.confirm(&GUIEvents::Crash(4)).is_none());
+        // Links are confirmed in either direction
+        let confirmed = pending.confirm(&GUIEvents::AddSender(2, 1));
+        assert_eq!(confirmed.map(|pending| pending.journal_index), Some(0));
+        let confirmed = pending.confirm(&GUIEvents::Crash(3));
+        assert_eq!(confirmed.map(|pending| pending.journal_index), Some(1));
+
+        assert_eq!(pending.commands().len(), 1);
+        assert_eq!(pending.edges().collect::<Vec<_>>(), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn a_pdr_stays_pending_until_it_expires() {
+        let mut pending = PendingCommands::with_timeout(Duration::ZERO);
+        pending.track(GUICommands::SetPDR(3, 0.2), 0);
+        pending.track(GUICommands::SetPDR(3, 0.4), 1);
+
+        assert_eq!(pending.pdr(3), Some(0.4));
+        assert_eq!(pending.nodes().collect::<Vec<_>>(), vec![3, 3]);
+        assert!(pending
+            .confirm(&GUIEvents::Spawn(3, vec![1], 0.2))
+            .is_none());
+
+        let expired = pending.expire();
+        assert_eq!(expired.len(), 2);
+        assert!(!PendingCommands::expects_confirmation(&expired[0].command));
+        assert_eq!(pending.pdr(3), None);
+    }
+
+    #[test]
+    fn expires_commands_older_than_the_timeout() {
+        let mut pending = PendingCommands::with_timeout(Duration::ZERO);
+        pending.track(GUICommands::Crash(3), 0);
+        pending.track(GUICommands::RegisterTo(10, 20), 1);
+
+        let expired = pending.expire();
+        assert_eq!(expired.len(), 2);
+        assert!(pending.is_empty());
+        assert!(PendingCommands::expects_confirmation(&expired[0].command));
+        assert!(!PendingCommands::expects_confirmation(&expired[1].command));
+
+        let mut pending = PendingCommands::new();
+        pending.track(GUICommands::Crash(3), 0);
+        assert!(pending.expire().is_empty());
+        assert_eq!(pending.commands().len(), 1);
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use eframe::egui::Vec2;
+use serde::{Deserialize, Serialize};
+use wg_2024::network::NodeId;
+
+use crate::logic::{error::GuiError, graph::NetworkGraph, layout, nodes::NodeGUI};
+
+/// Node positions saved to a file, to be reapplied when the same topology is loaded again
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedPositions {
+    /// Size of the canvas the positions were saved on, used to rescale them
+    pub canvas: (f32, f32),
+    pub positions: BTreeMap<NodeId, (f32, f32)>,
+}
+
+impl SavedPositions {
+    /// The current position of every node
+    #[must_use]
+    pub fn capture(nodes: &HashMap<NodeId, NodeGUI>, canvas_size: Vec2) -> Self {
+        Self {
+            canvas: (canvas_size.x, canvas_size.y),
+            positions: nodes
+                .iter()
+                .map(|(id, node)| (*id, (node.x, node.y)))
+                .collect(),
+        }
+    }
+
+    /// Write the positions as JSON
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GuiError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| GuiError::file(path, &e))?;
+        let mut writer = BufWriter::new(file);
+
+        serde_json::to_writer_pretty(&mut writer, self).map_err(|e| GuiError::file(path, &e))?;
+        writer.flush().map_err(|e| GuiError::file(path, &e))
+    }
+
+    /// Read positions written by [`SavedPositions::save`]
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the file cannot be read or is not valid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GuiError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| GuiError::file(path, &e))?;
+
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| GuiError::file(path, &e))
+    }
+
+    /// Whether the positions were saved for exactly the nodes of `graph`
+    #[must_use]
+    pub fn matches(&self, graph: &NetworkGraph) -> bool {
+        self.positions.len() == graph.node_count()
+            && graph.nodes().all(|id| self.positions.contains_key(&id))
+    }
+
+    /// The positions, rescaled from the canvas they were saved on to one of `size`
+    #[must_use]
+    pub fn scaled(&self, size: Vec2) -> HashMap<NodeId, (f32, f32)> {
+        let canvas = Vec2::new(self.canvas.0, self.canvas.1);
+
+        self.positions
+            .iter()
+            .map(|(id, position)| (*id, layout::rescale(*position, canvas, size, canvas)))
+            .collect()
+    }
+}
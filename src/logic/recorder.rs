@@ -0,0 +1,164 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+use messages::gui_commands::GUIEvents;
+
+use crate::logic::error::GuiError;
+
+/// A `GUIEvents` together with the time it was received, relative to the start of the recording
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub elapsed_ms: u64,
+    pub event: GUIEvents,
+}
+
+/// Borrowing counterpart of [`RecordedEvent`], to avoid cloning events while recording
+#[derive(Serialize)]
+struct RecordedLine<'a> {
+    elapsed_ms: u64,
+    event: &'a GUIEvents,
+}
+
+/// Writes every incoming `GUIEvents` to a JSON-lines file
+pub struct EventRecorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    started: Instant,
+    recorded: usize,
+}
+
+impl EventRecorder {
+    /// Create (or truncate) the recording file
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, GuiError> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path).map_err(|e| GuiError::file(&path, &e))?;
+
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            started: Instant::now(),
+            recorded: 0,
+        })
+    }
+
+    /// Append an event to the recording
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the event cannot be serialized or written.
+    pub fn record(&mut self, event: &GUIEvents) -> Result<(), GuiError> {
+        let elapsed_ms = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        serde_json::to_writer(&mut self.writer, &RecordedLine { elapsed_ms, event })
+            .map_err(|e| GuiError::file(&self.path, &e))?;
+        self.writer
+            .write_all(b"\n")
+            .map_err(|e| GuiError::file(&self.path, &e))?;
+
+        self.recorded += 1;
+        Ok(())
+    }
+
+    /// Flush buffered events to disk
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the file cannot be written.
+    pub fn flush(&mut self) -> Result<(), GuiError> {
+        self.writer
+            .flush()
+            .map_err(|e| GuiError::file(&self.path, &e))
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[must_use]
+    pub fn recorded(&self) -> usize {
+        self.recorded
+    }
+}
+
+impl Drop for EventRecorder {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+/// Read a recording written by [`EventRecorder`]
+///
+/// # Errors
+/// Returns [`GuiError::File`] if the file cannot be read or a line is not a valid event.
+pub fn load_recording(path: impl AsRef<Path>) -> Result<Vec<RecordedEvent>, GuiError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| GuiError::file(path, &e))?;
+
+    let mut events = Vec::new();
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| GuiError::file(path, &e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line).map_err(|e| GuiError::File {
+            path: path.display().to_string(),
+            reason: format!("line {}: {e}", number + 1),
+        })?;
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_events_load_back_in_order() {
+        let path = std::env::temp_dir().join(format!("gui-recording-{}.jsonl", std::process::id()));
+
+        let mut recorder = EventRecorder::create(&path).unwrap();
+        recorder.record(&GUIEvents::Crash(3)).unwrap();
+        recorder
+            .record(&GUIEvents::Spawn(7, vec![1, 2], 0.25))
+            .unwrap();
+        recorder.flush().unwrap();
+        assert_eq!(recorder.recorded(), 2);
+        assert_eq!(recorder.path(), path.as_path());
+        drop(recorder);
+
+        let events = load_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert!(events[0].elapsed_ms <= events[1].elapsed_ms);
+        assert!(matches!(events[0].event, GUIEvents::Crash(3)));
+        assert!(matches!(
+            events[1].event,
+            GUIEvents::Spawn(7, ref neighbors, pdr)
+                if neighbors == &[1, 2] && (pdr - 0.25).abs() < f32::EPSILON
+        ));
+    }
+
+    #[test]
+    fn an_invalid_line_names_its_number() {
+        let path = std::env::temp_dir().join(format!("gui-invalid-{}.jsonl", std::process::id()));
+        std::fs::write(&path, "\nnot json\n").unwrap();
+
+        let result = load_recording(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(
+            matches!(result, Err(GuiError::File { ref reason, .. }) if reason.starts_with("line 2"))
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::{path::Path, time::Instant};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+use messages::gui_commands::{GUICommands, GUIEvents};
+
+use crate::logic::{
+    error::GuiError,
+    recorder::{load_recording, RecordedEvent},
+};
+
+/// Feeds a recording back into the GUI in place of a Simulation Controller
+pub struct Replay {
+    events: Vec<RecordedEvent>,
+    position: usize,
+    clock_ms: f64,
+    last_update: Option<Instant>,
+    event_send: Sender<GUIEvents>,
+    // Commands issued during a replay go nowhere, but the channel must stay open
+    _command_recv: Receiver<GUICommands>,
+    pub playing: bool,
+    pub speed: f32,
+}
+
+impl Replay {
+    /// Load a recording and create the channel ends to hand to the GUI
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the recording cannot be loaded.
+    pub fn load(
+        path: impl AsRef<Path>,
+    ) -> Result<(Self, Sender<GUICommands>, Receiver<GUIEvents>), GuiError> {
+        let events = load_recording(path)?;
+        let (command_send, command_recv) = unbounded();
+        let (event_send, event_recv) = unbounded();
+
+        let replay = Self {
+            events,
+            position: 0,
+            clock_ms: 0.0,
+            last_update: None,
+            event_send,
+            _command_recv: command_recv,
+            playing: true,
+            speed: 1.0,
+        };
+
+        Ok((replay, command_send, event_recv))
+    }
+
+    /// Advance the replay clock and send every event that became due
+    pub fn feed(&mut self) {
+        let now = Instant::now();
+        if self.playing {
+            if let Some(last) = self.last_update {
+                self.clock_ms +=
+                    now.duration_since(last).as_secs_f64() * 1000.0 * f64::from(self.speed);
+            }
+        }
+        self.last_update = Some(now);
+
+        while let Some(recorded) = self.events.get(self.position) {
+            #[allow(clippy::cast_precision_loss)]
+            if recorded.elapsed_ms as f64 > self.clock_ms {
+                break;
+            }
+            if self.event_send.send(recorded.event.clone()).is_err() {
+                break;
+            }
+            self.position += 1;
+        }
+    }
+
+    /// Send the next event immediately, regardless of its timestamp
+    pub fn step(&mut self) {
+        if let Some(recorded) = self.events.get(self.position) {
+            #[allow(clippy::cast_precision_loss)]
+            {
+                self.clock_ms = self.clock_ms.max(recorded.elapsed_ms as f64);
+            }
+            if self.event_send.send(recorded.event.clone()).is_ok() {
+                self.position += 1;
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.events.len()
+    }
+
+    /// Current position of the replay clock
+    #[must_use]
+    pub fn clock_ms(&self) -> f64 {
+        self.clock_ms
+    }
+
+    /// Timestamp of the last recorded event
+    #[must_use]
+    pub fn duration_ms(&self) -> u64 {
+        self.events.last().map_or(0, |recorded| recorded.elapsed_ms)
+    }
+}
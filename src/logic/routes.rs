@@ -0,0 +1,273 @@
+use std::{collections::HashMap, time::Instant};
+
+use wg_2024::{network::NodeId, packet::Packet};
+
+use crate::{constants::MAX_ROUTE_TRACES, logic::traffic::PacketKind};
+
+/// The packets of a session sent by one node. Acks and Nacks share the session id of the
+/// fragments they answer but travel back from the destination, so they are traced apart
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceKey {
+    pub session_id: u64,
+    pub source: NodeId,
+}
+
+impl TraceKey {
+    /// The trace `packet` belongs to, `None` if it has no route
+    #[must_use]
+    pub fn of(packet: &Packet) -> Option<Self> {
+        Some(Self {
+            session_id: packet.session_id,
+            source: *packet.routing_header.hops.first()?,
+        })
+    }
+}
+
+/// Where the packets of a session went, compared to the route planned by their source
+#[derive(Clone, Debug)]
+pub struct RouteTrace {
+    pub kind: PacketKind,
+    /// Hops of the source routing header
+    pub planned: Vec<NodeId>,
+    /// Index in `planned` of the furthest hop reached
+    pub reached: usize,
+    /// Links the packets were sent over, in the order they were first used
+    pub travelled: Vec<(NodeId, NodeId)>,
+    /// First node that sent a packet off the planned route
+    pub diverged_at: Option<NodeId>,
+    /// Last node that dropped a packet of the session
+    pub dropped_at: Option<NodeId>,
+    /// Number of times a packet of the session was sent over a link
+    pub transmissions: u32,
+    pub last_seen: Instant,
+}
+
+impl RouteTrace {
+    fn new(packet: &Packet) -> Self {
+        Self {
+            kind: PacketKind::of(&packet.pack_type),
+            planned: packet.routing_header.hops.clone(),
+            reached: 0,
+            travelled: Vec::new(),
+            diverged_at: None,
+            dropped_at: None,
+            transmissions: 0,
+            last_seen: Instant::now(),
+        }
+    }
+
+    #[must_use]
+    pub fn destination(&self) -> Option<NodeId> {
+        self.planned.last().copied()
+    }
+
+    /// Whether a packet reached the last hop of the route
+    #[must_use]
+    pub fn is_delivered(&self) -> bool {
+        self.reached + 1 >= self.planned.len()
+    }
+
+    /// Whether the link from `src` to `dest` is part of the planned route
+    #[must_use]
+    pub fn is_planned(&self, src: NodeId, dest: NodeId) -> bool {
+        self.planned.windows(2).any(|hop| hop == [src, dest])
+    }
+
+    fn reach(&mut self, node: NodeId) {
+        if let Some(position) = self.planned.iter().position(|hop| *hop == node) {
+            self.reached = self.reached.max(position);
+        }
+    }
+}
+
+/// Routes of the sessions seen recently, and the one traced on the canvas
+#[derive(Clone, Debug, Default)]
+pub struct RouteTraces {
+    pub selected: Option<TraceKey>,
+    traces: HashMap<TraceKey, RouteTrace>,
+}
+
+impl RouteTraces {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a packet sent from `src` to `dest`
+    pub fn record_sent(&mut self, src: NodeId, dest: NodeId, packet: &Packet) {
+        let Some(trace) = self.trace_mut(packet) else {
+            return;
+        };
+        trace.transmissions += 1;
+        trace.reach(dest);
+
+        if !trace.travelled.contains(&(src, dest)) {
+            trace.travelled.push((src, dest));
+        }
+        if trace.diverged_at.is_none() && !trace.is_planned(src, dest) {
+            trace.diverged_at = Some(src);
+        }
+    }
+
+    /// Record a packet dropped by `dropper`
+    pub fn record_dropped(&mut self, dropper: NodeId, packet: &Packet) {
+        let Some(trace) = self.trace_mut(packet) else {
+            return;
+        };
+        trace.reach(dropper);
+        trace.dropped_at = Some(dropper);
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &TraceKey) -> Option<&RouteTrace> {
+        self.traces.get(key)
+    }
+
+    /// The trace shown on the canvas, if it is still recorded
+    #[must_use]
+    pub fn selected(&self) -> Option<&RouteTrace> {
+        self.selected.as_ref().and_then(|key| self.traces.get(key))
+    }
+
+    /// Every trace, most recently seen first
+    #[must_use]
+    pub fn recent(&self) -> Vec<(TraceKey, &RouteTrace)> {
+        let mut traces: Vec<(TraceKey, &RouteTrace)> = self
+            .traces
+            .iter()
+            .map(|(key, trace)| (*key, trace))
+            .collect();
+        traces.sort_by_key(|(_, trace)| std::cmp::Reverse(trace.last_seen));
+        traces
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.traces.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.traces.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.traces.clear();
+        self.selected = None;
+    }
+
+    /// The trace of `packet`, started over if its source picked another route
+    fn trace_mut(&mut self, packet: &Packet) -> Option<&mut RouteTrace> {
+        let key = TraceKey::of(packet)?;
+
+        match self.traces.get(&key) {
+            Some(trace) if trace.planned == packet.routing_header.hops => {}
+            Some(_) => {
+                self.traces.insert(key, RouteTrace::new(packet));
+            }
+            None => {
+                self.evict();
+                self.traces.insert(key, RouteTrace::new(packet));
+            }
+        }
+
+        let trace = self.traces.get_mut(&key)?;
+        trace.last_seen = Instant::now();
+        Some(trace)
+    }
+
+    /// Make room for a new trace by forgetting the oldest one, unless it is selected
+    fn evict(&mut self) {
+        if self.traces.len() < MAX_ROUTE_TRACES {
+            return;
+        }
+        let oldest = self
+            .traces
+            .iter()
+            .filter(|(key, _)| Some(**key) != self.selected)
+            .min_by_key(|(_, trace)| trace.last_seen)
+            .map(|(key, _)| *key);
+        if let Some(key) = oldest {
+            self.traces.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wg_2024::{
+        network::SourceRoutingHeader,
+        packet::{Ack, PacketType},
+    };
+
+    use super::*;
+
+    fn ack(session_id: u64, hops: &[NodeId]) -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 1,
+                hops: hops.to_vec(),
+            },
+            session_id,
+        }
+    }
+
+    #[test]
+    fn follows_the_planned_route_until_delivery() {
+        let mut traces = RouteTraces::new();
+        let packet = ack(1, &[10, 1, 2, 20]);
+        let key = TraceKey::of(&packet).unwrap();
+
+        traces.record_sent(10, 1, &packet);
+        traces.record_sent(1, 2, &packet);
+        let trace = traces.get(&key).unwrap();
+        assert_eq!(trace.reached, 2);
+        assert!(!trace.is_delivered());
+        assert!(trace.diverged_at.is_none());
+
+        traces.record_sent(2, 20, &packet);
+        let trace = traces.get(&key).unwrap();
+        assert!(trace.is_delivered());
+        assert_eq!(trace.travelled, vec![(10, 1), (1, 2), (2, 20)]);
+        assert_eq!(trace.destination(), Some(20));
+    }
+
+    #[test]
+    fn records_divergences_drops_and_new_routes() {
+        let mut traces = RouteTraces::new();
+        let packet = ack(1, &[10, 1, 2, 20]);
+        let key = TraceKey::of(&packet).unwrap();
+
+        traces.record_sent(10, 1, &packet);
+        traces.record_sent(1, 3, &packet);
+        traces.record_dropped(3, &packet);
+        let trace = traces.get(&key).unwrap();
+        assert_eq!(trace.diverged_at, Some(1));
+        assert_eq!(trace.dropped_at, Some(3));
+        assert_eq!(trace.transmissions, 2);
+
+        // The source picked another route for the same session
+        let rerouted = ack(1, &[10, 1, 3, 20]);
+        traces.record_sent(10, 1, &rerouted);
+        let trace = traces.get(&key).unwrap();
+        assert_eq!(trace.planned, vec![10, 1, 3, 20]);
+        assert_eq!(trace.transmissions, 1);
+        assert!(trace.diverged_at.is_none());
+    }
+
+    #[test]
+    fn keeps_the_selected_trace_when_full() {
+        let mut traces = RouteTraces::new();
+        let first = ack(0, &[10, 1]);
+        traces.record_sent(10, 1, &first);
+        traces.selected = TraceKey::of(&first);
+
+        for session_id in 1..=MAX_ROUTE_TRACES as u64 {
+            traces.record_sent(10, 1, &ack(session_id, &[10, 1]));
+        }
+
+        assert_eq!(traces.len(), MAX_ROUTE_TRACES);
+        assert!(traces.selected().is_some());
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+
+use wg_2024::network::NodeId;
+
+use crate::logic::graph::NetworkGraph;
+
+/// Nodes selected on the canvas for bulk actions, and two groups captured from the
+/// selection to cut the links between them
+#[derive(Clone, Debug, Default)]
+pub struct Selection {
+    nodes: BTreeSet<NodeId>,
+    pub group_a: BTreeSet<NodeId>,
+    pub group_b: BTreeSet<NodeId>,
+}
+
+impl Selection {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.nodes.contains(&id)
+    }
+
+    /// Add `id` to the selection, or remove it if it was already selected
+    pub fn toggle(&mut self, id: NodeId) {
+        if !self.nodes.remove(&id) {
+            self.nodes.insert(id);
+        }
+    }
+
+    pub fn extend(&mut self, ids: impl IntoIterator<Item = NodeId>) {
+        self.nodes.extend(ids);
+    }
+
+    /// Forget a node that left the network
+    pub fn remove(&mut self, id: NodeId) {
+        self.nodes.remove(&id);
+        self.group_a.remove(&id);
+        self.group_b.remove(&id);
+    }
+
+    /// Selected nodes, in increasing id order
+    pub fn iter(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.iter().copied()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Whether a group was captured from the selection
+    #[must_use]
+    pub fn has_groups(&self) -> bool {
+        !self.group_a.is_empty() || !self.group_b.is_empty()
+    }
+
+    /// Deselect every node, keeping the groups
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    /// Deselect every node and forget the groups
+    pub fn reset(&mut self) {
+        self.nodes.clear();
+        self.group_a.clear();
+        self.group_b.clear();
+    }
+
+    /// Links of `graph` with one end in each group
+    #[must_use]
+    pub fn links_between_groups(&self, graph: &NetworkGraph) -> Vec<(NodeId, NodeId)> {
+        graph
+            .edges()
+            .filter_map(|(a, b)| {
+                if self.group_a.contains(&a) && self.group_b.contains(&b) {
+                    Some((a, b))
+                } else if self.group_a.contains(&b) && self.group_b.contains(&a) {
+                    Some((b, a))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggles_and_forgets_nodes() {
+        let mut selection = Selection::new();
+        selection.extend([1, 2, 3]);
+        selection.toggle(2);
+        selection.toggle(4);
+        assert_eq!(selection.iter().collect::<Vec<_>>(), vec![1, 3, 4]);
+
+        selection.group_a = [1, 3].into();
+        selection.remove(3);
+        assert!(!selection.contains(3));
+        assert_eq!(selection.group_a, [1].into());
+
+        selection.clear();
+        assert!(selection.is_empty() && selection.has_groups());
+        selection.reset();
+        assert!(!selection.has_groups());
+    }
+
+    #[test]
+    fn links_between_groups_go_from_a_to_b() {
+        let mut graph = NetworkGraph::new();
+        for id in [1, 2, 3, 4] {
+            graph.add_node(id);
+        }
+        for (a, b) in [(1, 2), (3, 1), (2, 3), (3, 4)] {
+            graph.add_edge(a, b).unwrap();
+        }
+
+        let selection = Selection {
+            group_a: [1, 2].into(),
+            group_b: [3].into(),
+            ..Selection::default()
+        };
+        let mut links = selection.links_between_groups(&graph);
+        links.sort_unstable();
+
+        assert_eq!(links, vec![(1, 3), (2, 3)]);
+    }
+}
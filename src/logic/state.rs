@@ -1,24 +1,69 @@
-use crossbeam_channel::{Receiver, Sender};
-use std::collections::HashMap;
+use crossbeam_channel::{Receiver, SendError, Sender};
+use eframe::egui::Vec2;
+use std::{collections::HashMap, time::Instant};
 
-use eframe::egui::Color32;
+use colored::Colorize;
+use log::error;
 
 use wg_2024::network::NodeId;
 
 use messages::gui_commands::{GUICommands, GUIEvents};
 
-use crate::logic::nodes::NodeGUI;
+use crate::{
+    constants::{HEIGHT, MAX_ERROR_RECORDS, WIDTH},
+    logic::{
+        animation::PacketAnimations,
+        error::{ErrorRecord, GuiError},
+        floods::FloodTraces,
+        graph::NetworkGraph,
+        history::EditHistory,
+        journal::{CommandJournal, CommandOutcome},
+        layout::{self, Layout, LayoutTransition},
+        live_layout::LiveLayout,
+        metrics::NetworkMetrics,
+        nodes::NodeGUI,
+        pdr::PdrChecks,
+        pending::PendingCommands,
+        positions::SavedPositions,
+        routes::RouteTraces,
+        selection::Selection,
+        traffic::TrafficStats,
+    },
+};
 
 /// Main GUI state container
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug)]
 pub struct GUIState {
     pub sender: Sender<GUICommands>,
     pub receiver: Receiver<GUIEvents>,
+    // Set once the Simulation Controller hung up, the channel is no longer polled
+    pub disconnected: bool,
 
     // Core state
     pub initialized: bool,
     pub nodes: HashMap<NodeId, NodeGUI>,
-    pub edges: HashMap<NodeId, (Vec<NodeId>, Color32)>,
+    pub graph: NetworkGraph,
+
+    // Nodes selected on the canvas for bulk actions
+    pub selection: Selection,
+
+    // Drones spawned recently, highlighted on the canvas
+    pub spawned: HashMap<NodeId, Instant>,
+
+    // Size of the network canvas, node positions are laid out within it, and size of the
+    // canvas the layout was computed for, which every resize scales from
+    pub canvas_size: Vec2,
+    pub layout_canvas: Vec2,
+
+    // Layout the nodes are placed with, and the animated move to it
+    pub layout: Layout,
+    pub layout_transition: Option<LayoutTransition>,
+    pub live_layout: Option<LiveLayout>,
+
+    // Seed making the force-directed layout reproducible, and positions saved to a file
+    pub layout_seed: Option<u64>,
+    pub saved_positions: Option<SavedPositions>,
 
     // Spawn drone state
     pub spawn: SpawnState,
@@ -28,6 +73,48 @@ pub struct GUIState {
 
     // Animation Managment
     pub show_animation: bool,
+    pub animations: PacketAnimations,
+
+    // Traffic heatmap
+    pub show_heatmap: bool,
+    pub traffic: TrafficStats,
+
+    // Route tracing: routes of recent sessions, and the one traced on the canvas
+    pub show_trace: bool,
+    pub routes: RouteTraces,
+
+    // Network discoveries, drawn as waves spreading over the graph
+    pub show_floods: bool,
+    pub floods: FloodTraces,
+
+    // Observed drop ratio of each drone
+    pub pdr_checks: PdrChecks,
+
+    // Per-second history shown in the dashboard
+    pub metrics: NetworkMetrics,
+
+    // Event ingestion statistics
+    pub ingestion: IngestionStats,
+
+    // Errors shown in the error panel
+    pub errors: Vec<ErrorRecord>,
+
+    // Every command issued to the Simulation Controller
+    pub journal: CommandJournal,
+
+    // Undo/redo stacks of topology edits
+    pub history: EditHistory,
+
+    // Commands waiting for the Simulation Controller to confirm them
+    pub pending: PendingCommands,
+}
+
+/// Statistics about the last frame of event ingestion
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IngestionStats {
+    pub backlog: usize,
+    pub processed: usize,
+    pub coalesced: usize,
 }
 
 /// State for spawning new drones
@@ -46,9 +133,19 @@ impl GUIState {
         Self {
             sender,
             receiver,
+            disconnected: false,
             initialized: false,
             nodes: HashMap::new(),
-            edges: HashMap::new(),
+            graph: NetworkGraph::new(),
+            selection: Selection::new(),
+            spawned: HashMap::new(),
+            canvas_size: Vec2::new(WIDTH, HEIGHT),
+            layout_canvas: Vec2::new(WIDTH, HEIGHT),
+            layout: Layout::ForceDirected,
+            layout_transition: None,
+            live_layout: None,
+            layout_seed: None,
+            saved_positions: None,
             spawn: SpawnState {
                 button_visible: true,
                 panel_open: false,
@@ -58,6 +155,20 @@ impl GUIState {
             },
             file_list: HashMap::new(),
             show_animation: false,
+            animations: PacketAnimations::new(),
+            show_heatmap: false,
+            traffic: TrafficStats::new(),
+            show_trace: false,
+            routes: RouteTraces::new(),
+            show_floods: false,
+            floods: FloodTraces::new(),
+            pdr_checks: PdrChecks::new(),
+            metrics: NetworkMetrics::new(),
+            ingestion: IngestionStats::default(),
+            errors: Vec::new(),
+            journal: CommandJournal::new(),
+            history: EditHistory::new(),
+            pending: PendingCommands::new(),
         }
     }
 
@@ -69,4 +180,128 @@ impl GUIState {
             ..Default::default()
         };
     }
+
+    /// Follow a resize of the network canvas, scaling node positions and the running
+    /// transition the same way on both axes so the layout keeps its shape, and comes back
+    /// unchanged once the canvas is resized back
+    pub fn resize_canvas(&mut self, size: Vec2) {
+        if size == self.canvas_size || size.min_elem() <= 0.0 {
+            return;
+        }
+
+        let (from, computed_on) = (self.canvas_size, self.layout_canvas);
+        for node in self.nodes.values_mut() {
+            (node.x, node.y) = layout::rescale((node.x, node.y), from, size, computed_on);
+        }
+        if let Some(transition) = &mut self.layout_transition {
+            transition.rescale(from, size, computed_on);
+        }
+        self.canvas_size = size;
+    }
+
+    /// Send a command to the Simulation Controller, recording it in the journal and
+    /// waiting for its confirmation
+    ///
+    /// # Errors
+    /// Returns the [`SendError`] if the Simulation Controller channel is closed.
+    pub fn send_command(&mut self, command: GUICommands) -> Result<(), SendError<GUICommands>> {
+        let result = self.sender.send(command.clone());
+        match &result {
+            Ok(()) => {
+                let index = self.journal.push(command.clone(), CommandOutcome::Sent);
+                self.pending.track(command, index);
+            }
+            Err(e) => {
+                self.journal
+                    .push(command, CommandOutcome::Failed(e.to_string()));
+            }
+        }
+        result
+    }
+
+    /// Mark the pending command confirmed by `event`, if any
+    pub fn confirm_command(&mut self, event: &GUIEvents) {
+        if let Some(confirmed) = self.pending.confirm(event) {
+            self.journal
+                .set_outcome(confirmed.journal_index, CommandOutcome::Confirmed);
+        }
+    }
+
+    /// Flag the pending commands the Simulation Controller did not confirm in time
+    pub fn expire_pending_commands(&mut self) {
+        for expired in self.pending.expire() {
+            if PendingCommands::expects_confirmation(&expired.command) {
+                self.journal
+                    .set_outcome(expired.journal_index, CommandOutcome::TimedOut);
+                self.record_error(GuiError::CommandTimeout {
+                    command: format!("GUICommand::{:?}", expired.command),
+                    timeout: self.pending.timeout,
+                });
+                continue;
+            }
+
+            self.journal
+                .set_outcome(expired.journal_index, CommandOutcome::Unconfirmed);
+            if let GUICommands::SetPDR(drone, pdr) = expired.command {
+                if let Some(instance) = self.nodes.get_mut(&drone) {
+                    instance.drone_params.unconfirmed_pdr = Some(pdr);
+                }
+            }
+        }
+    }
+
+    /// Check that `nodes` and `graph` describe the same set of nodes and that the graph
+    /// is well formed
+    ///
+    /// # Errors
+    /// Returns [`GuiError::InconsistentTopology`] describing the first problem found.
+    pub fn check_consistency(&self) -> Result<(), GuiError> {
+        self.graph.check()?;
+
+        if let Some(id) = self.nodes.keys().find(|id| !self.graph.contains_node(**id)) {
+            return Err(GuiError::InconsistentTopology(format!(
+                "node {id} is missing from the graph"
+            )));
+        }
+        if let Some(id) = self.graph.nodes().find(|id| !self.nodes.contains_key(id)) {
+            return Err(GuiError::InconsistentTopology(format!(
+                "graph node {id} has no GUI node"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Record an error so it shows up in the error panel
+    pub fn record_error(&mut self, error: GuiError) {
+        error!("[ {} ] {}", "GUI".red(), error);
+
+        // Repeated errors are folded into the latest record instead of flooding the panel
+        if let Some(last) = self.errors.last_mut() {
+            if last.error == error {
+                last.occurrences += 1;
+                last.last_seen = Instant::now();
+                return;
+            }
+        }
+
+        if self.errors.len() >= MAX_ERROR_RECORDS {
+            self.errors.remove(0);
+        }
+        self.errors.push(ErrorRecord::new(error));
+    }
+
+    /// Record the error of a failed operation, if any
+    pub fn report(&mut self, result: Result<(), GuiError>) {
+        if let Err(e) = result {
+            self.record_error(e);
+        }
+    }
+
+    /// Remove an error from the error panel
+    pub fn dismiss_error(&mut self, index: usize) {
+        if index < self.errors.len() {
+            self.errors.remove(index);
+        }
+    }
 }
@@ -0,0 +1,278 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use wg_2024::{network::NodeId, packet::PacketType};
+
+use crate::constants::{NODE_TRAFFIC_WINDOW_SECS, TRAFFIC_WINDOW_SECS};
+
+/// Counters below this value are considered idle and discarded
+const IDLE_COUNT: f32 = 0.01;
+
+/// Packet count that decays exponentially over the sliding window
+#[derive(Clone, Copy, Debug)]
+struct DecayingCounter {
+    value: f32,
+    updated: Instant,
+}
+
+impl DecayingCounter {
+    fn at(&self, now: Instant, window: Duration) -> f32 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f32();
+        self.value * (-elapsed / window.as_secs_f32()).exp()
+    }
+
+    fn add(&mut self, now: Instant, window: Duration) {
+        self.value = self.at(now, window) + 1.0;
+        self.updated = now;
+    }
+}
+
+/// Kind of a packet, as counted in the traffic statistics
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PacketKind {
+    MsgFragment,
+    Ack,
+    Nack,
+    FloodRequest,
+    FloodResponse,
+}
+
+impl PacketKind {
+    pub const ALL: [Self; 5] = [
+        Self::MsgFragment,
+        Self::Ack,
+        Self::Nack,
+        Self::FloodRequest,
+        Self::FloodResponse,
+    ];
+
+    #[must_use]
+    pub fn of(pack_type: &PacketType) -> Self {
+        match pack_type {
+            PacketType::MsgFragment(_) => Self::MsgFragment,
+            PacketType::Ack(_) => Self::Ack,
+            PacketType::Nack(_) => Self::Nack,
+            PacketType::FloodRequest(_) => Self::FloodRequest,
+            PacketType::FloodResponse(_) => Self::FloodResponse,
+        }
+    }
+
+    #[must_use]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Packets counted during one second
+#[derive(Clone, Copy, Debug, Default)]
+struct Second {
+    sent: [u32; PacketKind::ALL.len()],
+    dropped: u32,
+}
+
+/// Packets sent and dropped by a single node
+#[derive(Clone, Debug)]
+pub struct NodeTraffic {
+    sent: [u64; PacketKind::ALL.len()],
+    dropped: u64,
+    // Per-second counts of the last `NODE_TRAFFIC_WINDOW_SECS` seconds, newest last
+    seconds: VecDeque<Second>,
+    current_start: Instant,
+}
+
+impl NodeTraffic {
+    fn new(now: Instant) -> Self {
+        Self {
+            sent: [0; PacketKind::ALL.len()],
+            dropped: 0,
+            seconds: VecDeque::from([Second::default()]),
+            current_start: now,
+        }
+    }
+
+    /// Total packets of `kind` sent by the node
+    #[must_use]
+    pub fn sent(&self, kind: PacketKind) -> u64 {
+        self.sent[kind.index()]
+    }
+
+    /// Total packets sent by the node
+    #[must_use]
+    pub fn total_sent(&self) -> u64 {
+        self.sent.iter().sum()
+    }
+
+    /// Total packets dropped by the node
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Packets of `kind` sent per second over the last `NODE_TRAFFIC_WINDOW_SECS` seconds
+    #[must_use]
+    pub fn rate(&self, kind: PacketKind) -> f32 {
+        Self::average(self.window().iter().map(|second| second.sent[kind.index()]))
+    }
+
+    /// Packets dropped per second over the last `NODE_TRAFFIC_WINDOW_SECS` seconds
+    #[must_use]
+    pub fn drop_rate(&self) -> f32 {
+        Self::average(self.window().iter().map(|second| second.dropped))
+    }
+
+    /// Packets sent and dropped in each of the last `NODE_TRAFFIC_WINDOW_SECS` seconds,
+    /// oldest first
+    #[must_use]
+    pub fn sparkline(&self) -> Vec<u32> {
+        self.window()
+            .iter()
+            .map(|second| second.sent.iter().sum::<u32>() + second.dropped)
+            .collect()
+    }
+
+    fn record_sent(&mut self, kind: PacketKind, now: Instant) {
+        self.sent[kind.index()] += 1;
+        self.current(now).sent[kind.index()] += 1;
+    }
+
+    fn record_dropped(&mut self, now: Instant) {
+        self.dropped += 1;
+        self.current(now).dropped += 1;
+    }
+
+    /// The bucket of the running second, starting new ones as time passes
+    fn current(&mut self, now: Instant) -> &mut Second {
+        let elapsed = now.saturating_duration_since(self.current_start).as_secs();
+        if elapsed > 0 {
+            let new_seconds = usize::try_from(elapsed)
+                .unwrap_or(usize::MAX)
+                .min(NODE_TRAFFIC_WINDOW_SECS);
+            self.seconds
+                .extend(std::iter::repeat_n(Second::default(), new_seconds));
+            while self.seconds.len() > NODE_TRAFFIC_WINDOW_SECS {
+                self.seconds.pop_front();
+            }
+            self.current_start += Duration::from_secs(elapsed);
+        }
+        self.seconds
+            .back_mut()
+            .expect("there is always a running second")
+    }
+
+    /// The last `NODE_TRAFFIC_WINDOW_SECS` seconds, including the idle ones since the last
+    /// packet and before the first one
+    fn window(&self) -> Vec<Second> {
+        let idle = usize::try_from(self.current_start.elapsed().as_secs()).unwrap_or(usize::MAX);
+        let recorded = self.seconds.iter().copied().chain(std::iter::repeat_n(
+            Second::default(),
+            idle.min(NODE_TRAFFIC_WINDOW_SECS),
+        ));
+
+        let mut window = vec![Second::default(); NODE_TRAFFIC_WINDOW_SECS];
+        window.extend(recorded);
+        window.split_off(window.len() - NODE_TRAFFIC_WINDOW_SECS)
+    }
+
+    fn average(counts: impl Iterator<Item = u32>) -> f32 {
+        #[allow(clippy::cast_precision_loss)]
+        let average = counts.map(u64::from).sum::<u64>() as f32 / NODE_TRAFFIC_WINDOW_SECS as f32;
+        average
+    }
+}
+
+/// Packet rates observed on the links and nodes of the network
+#[derive(Clone, Debug)]
+pub struct TrafficStats {
+    /// Time over which old packets fade out of the rates
+    pub window: Duration,
+    edges: HashMap<(NodeId, NodeId), DecayingCounter>,
+    /// Total packets sent over each link, per direction
+    links: HashMap<(NodeId, NodeId), u64>,
+    nodes: HashMap<NodeId, NodeTraffic>,
+}
+
+impl Default for TrafficStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrafficStats {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            window: Duration::from_secs_f32(TRAFFIC_WINDOW_SECS),
+            edges: HashMap::new(),
+            links: HashMap::new(),
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Count a packet sent by `src` over the link to `dest`
+    pub fn record_packet(&mut self, src: NodeId, dest: NodeId, pack_type: &PacketType) {
+        let now = Instant::now();
+        self.nodes
+            .entry(src)
+            .or_insert_with(|| NodeTraffic::new(now))
+            .record_sent(PacketKind::of(pack_type), now);
+        *self.links.entry((src, dest)).or_insert(0) += 1;
+
+        let window = self.window;
+        self.edges
+            .entry(Self::link(src, dest))
+            .or_insert(DecayingCounter {
+                value: 0.0,
+                updated: now,
+            })
+            .add(now, window);
+    }
+
+    /// Packets per second recently sent over the link between `a` and `b`, in either direction
+    #[must_use]
+    pub fn edge_rate(&self, a: NodeId, b: NodeId) -> f32 {
+        self.edges.get(&Self::link(a, b)).map_or(0.0, |counter| {
+            counter.at(Instant::now(), self.window) / self.window.as_secs_f32()
+        })
+    }
+
+    /// Total packets sent by `src` over the link to `dest`
+    #[must_use]
+    pub fn link_sent(&self, src: NodeId, dest: NodeId) -> u64 {
+        self.links.get(&(src, dest)).copied().unwrap_or(0)
+    }
+
+    /// Count a packet dropped by `node`
+    pub fn record_drop(&mut self, node: NodeId) {
+        let now = Instant::now();
+        self.nodes
+            .entry(node)
+            .or_insert_with(|| NodeTraffic::new(now))
+            .record_dropped(now);
+    }
+
+    /// Packets sent and dropped by `node`, if it handled any
+    #[must_use]
+    pub fn node(&self, id: NodeId) -> Option<&NodeTraffic> {
+        self.nodes.get(&id)
+    }
+
+    /// Discard the counters of links that have been idle for a while
+    pub fn prune(&mut self) {
+        let now = Instant::now();
+        let window = self.window;
+        self.edges
+            .retain(|_, counter| counter.at(now, window) > IDLE_COUNT);
+    }
+
+    pub fn clear(&mut self) {
+        self.edges.clear();
+        self.links.clear();
+        self.nodes.clear();
+    }
+
+    fn link(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
+        (a.min(b), a.max(b))
+    }
+}
@@ -0,0 +1,511 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    path::Path,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use colored::Colorize;
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+use log::{info, warn};
+use rand::{rngs::StdRng, seq::IndexedRandom, Rng, SeedableRng};
+
+use wg_2024::{
+    config::Config,
+    network::{NodeId, SourceRoutingHeader},
+    packet::{
+        Ack, FloodRequest, FloodResponse, Fragment, Nack, NackType, NodeType, Packet, PacketType,
+        FRAGMENT_DSIZE,
+    },
+};
+
+use messages::gui_commands::{GUICommands, GUIEvents};
+
+use crate::{
+    constants::{MOCK_FLOOD_EVERY, MOCK_MAX_RETRIES, MOCK_TRAFFIC_INTERVAL_MS},
+    logic::error::GuiError,
+    mock::network::MockNetwork,
+};
+
+/// Behaviour of the mock Simulation Controller
+#[derive(Clone, Debug)]
+pub struct MockSettings {
+    /// Interval between two bursts of synthetic traffic
+    pub traffic_interval: Duration,
+    /// Number of traffic bursts between two network discoveries, `0` disables them
+    pub flood_every: u32,
+    /// Seed of the random generator, random when `None`
+    pub seed: Option<u64>,
+    /// Files advertised by every server
+    pub files: Vec<String>,
+}
+
+impl Default for MockSettings {
+    fn default() -> Self {
+        Self {
+            traffic_interval: Duration::from_millis(MOCK_TRAFFIC_INTERVAL_MS),
+            flood_every: MOCK_FLOOD_EVERY,
+            seed: None,
+            files: vec![
+                "lorem.txt".to_string(),
+                "readme.md".to_string(),
+                "report.html".to_string(),
+            ],
+        }
+    }
+}
+
+/// Local stand-in for the Simulation Controller.
+///
+/// It answers every `GUICommands` with plausible `GUIEvents` and keeps emitting synthetic
+/// `PacketSent`/`PacketDropped` traffic, honoring the PDR of each drone.
+pub struct MockController {
+    config: Config,
+    network: MockNetwork,
+    settings: MockSettings,
+    rng: StdRng,
+    commands: Receiver<GUICommands>,
+    events: Sender<GUIEvents>,
+    registrations: HashMap<NodeId, NodeId>,
+    next_session: u64,
+    next_flood: u64,
+    ticks: u32,
+    running: bool,
+}
+
+impl MockController {
+    /// Load a `wg_2024` network configuration file
+    ///
+    /// # Errors
+    /// Returns [`GuiError::File`] if the file cannot be read or is not a valid configuration.
+    pub fn load_config(path: impl AsRef<Path>) -> Result<Config, GuiError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| GuiError::file(path, &e))?;
+        toml::from_str(&content).map_err(|e| GuiError::file(path, &e))
+    }
+
+    #[must_use]
+    pub fn new(
+        config: Config,
+        settings: MockSettings,
+        commands: Receiver<GUICommands>,
+        events: Sender<GUIEvents>,
+    ) -> Self {
+        let rng = match settings.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        Self {
+            network: MockNetwork::from_config(&config),
+            config,
+            settings,
+            rng,
+            commands,
+            events,
+            registrations: HashMap::new(),
+            next_session: 0,
+            next_flood: 0,
+            ticks: 0,
+            running: true,
+        }
+    }
+
+    /// Start a mock controller on its own thread.
+    ///
+    /// Returns the channel ends to hand to the GUI and the handle of the controller thread,
+    /// which stops once the GUI drops its ends.
+    ///
+    /// # Panics
+    /// Panics if the operating system fails to create the thread.
+    #[must_use]
+    pub fn spawn(
+        config: Config,
+        settings: MockSettings,
+    ) -> (Sender<GUICommands>, Receiver<GUIEvents>, JoinHandle<()>) {
+        let (command_send, command_recv) = unbounded();
+        let (event_send, event_recv) = unbounded();
+
+        let controller = Self::new(config, settings, command_recv, event_send);
+        let handle = thread::Builder::new()
+            .name("mock-controller".to_string())
+            .spawn(move || controller.run())
+            .expect("failed to spawn the mock controller thread");
+
+        (command_send, event_recv, handle)
+    }
+
+    /// Send the topology, then serve commands and emit traffic until the GUI disconnects
+    pub fn run(mut self) {
+        info!("[ {} ] Started", "Mock Controller".green());
+
+        self.emit(GUIEvents::Topology(
+            self.config.drone.clone(),
+            self.config.client.clone(),
+            self.config.server.clone(),
+        ));
+
+        let mut next_tick = Instant::now() + self.settings.traffic_interval;
+        while self.running {
+            let timeout = next_tick.saturating_duration_since(Instant::now());
+            match self.commands.recv_timeout(timeout) {
+                Ok(command) => self.handle_command(command),
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if Instant::now() >= next_tick {
+                self.tick();
+                next_tick = Instant::now() + self.settings.traffic_interval;
+            }
+        }
+
+        info!("[ {} ] Stopped", "Mock Controller".green());
+    }
+
+    /// Answer a single command with the events a real controller would send
+    #[allow(clippy::too_many_lines)]
+    pub fn handle_command(&mut self, command: GUICommands) {
+        info!("[ {} ] Received {:?}", "Mock Controller".green(), command);
+
+        match command {
+            GUICommands::Crash(drone) => {
+                if self.network.crash(drone) {
+                    self.emit(GUIEvents::Crash(drone));
+                } else {
+                    Self::ignore(&format!("cannot crash {drone}, not a drone"));
+                }
+            }
+            GUICommands::SetPDR(drone, pdr) => match self.network.nodes.get_mut(&drone) {
+                // Like the Simulation Controller, there is no event for a new PDR
+                Some(node) if node.node_type == NodeType::Drone => node.pdr = pdr,
+                _ => Self::ignore(&format!("cannot set PDR of {drone}, not a drone")),
+            },
+            GUICommands::Spawn(id, neighbors, pdr) => {
+                match self.network.spawn(id, &neighbors, pdr) {
+                    Some(linked) => self.emit(GUIEvents::Spawn(id, linked, pdr)),
+                    None => Self::ignore(&format!("cannot spawn {id}, id already in use")),
+                }
+            }
+            GUICommands::AddSender(node_id, to_add) => {
+                if self.network.link(node_id, to_add) {
+                    self.emit(GUIEvents::AddSender(node_id, to_add));
+                    self.emit(GUIEvents::AddSender(to_add, node_id));
+                } else {
+                    Self::ignore(&format!("cannot link {node_id} and {to_add}"));
+                }
+            }
+            GUICommands::RemoveSender(node_id, to_remove) => {
+                if self.network.unlink(node_id, to_remove) {
+                    self.emit(GUIEvents::RemoveSender(node_id, to_remove));
+                    self.emit(GUIEvents::RemoveSender(to_remove, node_id));
+                } else {
+                    Self::ignore(&format!("{node_id} and {to_remove} are not linked"));
+                }
+            }
+            GUICommands::RegisterTo(client, server) => {
+                if self.exchange(client, server, 1, 1) {
+                    self.registrations.insert(client, server);
+                }
+            }
+            GUICommands::LogOut(client, server) => {
+                if self.registrations.get(&client) == Some(&server)
+                    && self.exchange(client, server, 1, 1)
+                {
+                    self.registrations.remove(&client);
+                }
+            }
+            GUICommands::GetClientList(client) => {
+                let Some(&server) = self.registrations.get(&client) else {
+                    self.emit(GUIEvents::ClientList(client, Vec::new()));
+                    return;
+                };
+                if self.exchange(client, server, 1, 1) {
+                    let mut clients: Vec<NodeId> = self
+                        .registrations
+                        .iter()
+                        .filter(|(_, s)| **s == server)
+                        .map(|(c, _)| *c)
+                        .collect();
+                    clients.sort_unstable();
+                    self.emit(GUIEvents::ClientList(client, clients));
+                }
+            }
+            GUICommands::SendMessageTo(src, dest, msg) => {
+                let server = self.registrations.get(&src).copied();
+                let fragments = msg.len().div_ceil(FRAGMENT_DSIZE).max(1);
+                let delivered = match server {
+                    Some(server) => {
+                        self.transfer(src, server, fragments)
+                            && self.transfer(server, dest, fragments)
+                    }
+                    None => self.transfer(src, dest, fragments),
+                };
+                if delivered {
+                    self.emit(GUIEvents::MessageReceived(src, dest, msg));
+                }
+            }
+            GUICommands::AskForFileList(client, server) => {
+                if self.exchange(client, server, 1, 1) {
+                    let files = self.settings.files.clone();
+                    self.emit(GUIEvents::FileList(server, client, files));
+                }
+            }
+            GUICommands::GetFile(client, server, title) => {
+                if !self.exchange(client, server, 1, 4) {
+                    Self::ignore(&format!(
+                        "file {title:?} could not be delivered to {client}"
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Emit one burst of synthetic traffic, or a network discovery every `flood_every` bursts
+    pub fn tick(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+
+        if self.settings.flood_every > 0 && self.ticks.is_multiple_of(self.settings.flood_every) {
+            let clients = self.network.ids_of(NodeType::Client);
+            if let Some(&initiator) = clients.choose(&mut self.rng) {
+                self.flood(initiator);
+            }
+            return;
+        }
+
+        let clients = self.network.ids_of(NodeType::Client);
+        let servers = self.network.ids_of(NodeType::Server);
+        if let (Some(&client), Some(&server)) =
+            (clients.choose(&mut self.rng), servers.choose(&mut self.rng))
+        {
+            let response_fragments = self.rng.random_range(1..=3);
+            self.exchange(client, server, 1, response_fragments);
+        }
+    }
+
+    /// A request from `client` to `server` followed by the response.
+    /// Returns `true` if both were delivered.
+    fn exchange(
+        &mut self,
+        client: NodeId,
+        server: NodeId,
+        request_fragments: usize,
+        response_fragments: usize,
+    ) -> bool {
+        self.transfer(client, server, request_fragments)
+            && self.transfer(server, client, response_fragments)
+    }
+
+    /// Deliver a message of `fragments` fragments, retransmitting dropped fragments.
+    /// Returns `true` if every fragment reached `dest`.
+    fn transfer(&mut self, src: NodeId, dest: NodeId, fragments: usize) -> bool {
+        let Some(path) = self.network.route(src, dest) else {
+            Self::ignore(&format!("no route from {src} to {dest}"));
+            return false;
+        };
+
+        let session_id = self.next_session;
+        self.next_session += 1;
+
+        let total = fragments as u64;
+        for fragment_index in 0..total {
+            let mut delivered = false;
+            for _ in 0..=MOCK_MAX_RETRIES {
+                let fragment = Fragment {
+                    fragment_index,
+                    total_n_fragments: total,
+                    length: 0,
+                    data: [0; FRAGMENT_DSIZE],
+                };
+                match self.forward(&path, &PacketType::MsgFragment(fragment), session_id) {
+                    Ok(()) => {
+                        let back: Vec<NodeId> = path.iter().rev().copied().collect();
+                        let ack = PacketType::Ack(Ack { fragment_index });
+                        let _ = self.forward(&back, &ack, session_id);
+                        delivered = true;
+                        break;
+                    }
+                    Err(dropped_at) => {
+                        let position = path.iter().position(|&n| n == dropped_at).unwrap_or(0);
+                        let back: Vec<NodeId> = path[..=position].iter().rev().copied().collect();
+                        let nack = PacketType::Nack(Nack {
+                            fragment_index,
+                            nack_type: NackType::Dropped,
+                        });
+                        let _ = self.forward(&back, &nack, session_id);
+                    }
+                }
+            }
+            if !delivered {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Send a packet hop by hop along `path`.
+    /// Fragments may be dropped by drones; returns the id of the dropping drone.
+    fn forward(
+        &mut self,
+        path: &[NodeId],
+        pack_type: &PacketType,
+        session_id: u64,
+    ) -> Result<(), NodeId> {
+        let droppable = matches!(pack_type, PacketType::MsgFragment(_));
+
+        for hop_index in 1..path.len() {
+            let packet = Packet {
+                pack_type: pack_type.clone(),
+                routing_header: SourceRoutingHeader {
+                    hop_index,
+                    hops: path.to_vec(),
+                },
+                session_id,
+            };
+            let (src, dest) = (path[hop_index - 1], path[hop_index]);
+            self.emit(GUIEvents::PacketSent(src, dest, packet.clone()));
+
+            if droppable && self.network.node_type(dest) == Some(NodeType::Drone) {
+                let pdr = f64::from(self.network.pdr(dest)).clamp(0.0, 1.0);
+                if self.rng.random_bool(pdr) {
+                    self.emit(GUIEvents::PacketDropped(dest, packet));
+                    return Err(dest);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Simulate a network discovery started by `initiator`
+    fn flood(&mut self, initiator: NodeId) {
+        let flood_id = self.next_flood;
+        self.next_flood += 1;
+        let session_id = self.next_session;
+        self.next_session += 1;
+
+        let Some(initiator_type) = self.network.node_type(initiator) else {
+            return;
+        };
+
+        // Breadth-first visit, only drones forward the request
+        let mut traces: HashMap<NodeId, Vec<(NodeId, NodeType)>> =
+            HashMap::from([(initiator, vec![(initiator, initiator_type)])]);
+        let mut queue = VecDeque::from([initiator]);
+        let mut leaves = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            let trace = traces[&current].clone();
+            let forwards =
+                current == initiator || self.network.node_type(current) == Some(NodeType::Drone);
+            let neighbors = self
+                .network
+                .nodes
+                .get(&current)
+                .map(|node| node.neighbors.clone())
+                .unwrap_or_default();
+
+            let mut reached_new = false;
+            if forwards {
+                for neighbor in neighbors {
+                    let Some(neighbor_type) = self.network.node_type(neighbor) else {
+                        continue;
+                    };
+                    if trace.iter().any(|(id, _)| *id == neighbor) {
+                        continue;
+                    }
+
+                    let request = FloodRequest {
+                        flood_id,
+                        initiator_id: initiator,
+                        path_trace: trace.clone(),
+                    };
+                    let packet = Packet {
+                        pack_type: PacketType::FloodRequest(request),
+                        routing_header: SourceRoutingHeader::default(),
+                        session_id,
+                    };
+                    self.emit(GUIEvents::PacketSent(current, neighbor, packet));
+
+                    if let Entry::Vacant(entry) = traces.entry(neighbor) {
+                        let mut next = trace.clone();
+                        next.push((neighbor, neighbor_type));
+                        entry.insert(next);
+                        queue.push_back(neighbor);
+                        reached_new = true;
+                    }
+                }
+            }
+
+            if !reached_new && current != initiator {
+                leaves.push(trace);
+            }
+        }
+
+        // Every node where the flood stopped answers along the reversed path trace
+        for trace in leaves {
+            let back: Vec<NodeId> = trace.iter().rev().map(|(id, _)| *id).collect();
+            let response = PacketType::FloodResponse(FloodResponse {
+                flood_id,
+                path_trace: trace,
+            });
+            let _ = self.forward(&back, &response, session_id);
+        }
+    }
+
+    fn emit(&mut self, event: GUIEvents) {
+        if self.running && self.events.send(event).is_err() {
+            warn!("[ {} ] GUI disconnected", "Mock Controller".yellow());
+            self.running = false;
+        }
+    }
+
+    fn ignore(reason: &str) {
+        warn!(
+            "[ {} ] Ignoring command: {}",
+            "Mock Controller".yellow(),
+            reason
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::fixtures::config;
+
+    #[test]
+    fn answers_commands_over_its_channels() {
+        let settings = MockSettings {
+            traffic_interval: Duration::from_secs(10),
+            flood_every: 0,
+            seed: Some(1),
+            ..MockSettings::default()
+        };
+        let (commands, events, controller) = MockController::spawn(config(), settings);
+        let next = || events.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(matches!(next(), GUIEvents::Topology(drones, _, _) if drones.len() == 2));
+
+        // A new PDR is applied silently, the next event answers the following command
+        commands.send(GUICommands::SetPDR(1, 0.0)).unwrap();
+        commands.send(GUICommands::RemoveSender(1, 2)).unwrap();
+        assert!(matches!(next(), GUIEvents::RemoveSender(1, 2)));
+        assert!(matches!(next(), GUIEvents::RemoveSender(2, 1)));
+
+        // Only the links to existing nodes are made
+        commands
+            .send(GUICommands::Spawn(3, vec![1, 99], 0.0))
+            .unwrap();
+        assert!(matches!(next(), GUIEvents::Spawn(3, linked, _) if linked == vec![1]));
+
+        // Clients cannot crash
+        commands.send(GUICommands::Crash(10)).unwrap();
+        commands.send(GUICommands::Crash(3)).unwrap();
+        assert!(matches!(next(), GUIEvents::Crash(3)));
+
+        drop(commands);
+        controller.join().unwrap();
+    }
+}
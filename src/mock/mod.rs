@@ -0,0 +1,5 @@
+pub mod controller;
+pub mod network;
+
+pub use controller::{MockController, MockSettings};
+pub use network::MockNetwork;
@@ -0,0 +1,178 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use wg_2024::{config::Config, network::NodeId, packet::NodeType};
+
+/// A node of the simulated network kept by the mock controller
+#[derive(Clone, Debug)]
+pub struct MockNode {
+    pub node_type: NodeType,
+    pub neighbors: Vec<NodeId>,
+    pub pdr: f32,
+}
+
+/// Topology tracked by the mock controller, kept symmetric on every change
+#[derive(Clone, Debug, Default)]
+pub struct MockNetwork {
+    pub nodes: HashMap<NodeId, MockNode>,
+}
+
+impl MockNetwork {
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        let mut network = Self::default();
+
+        for drone in &config.drone {
+            network.insert(drone.id, NodeType::Drone, drone.pdr);
+        }
+        for client in &config.client {
+            network.insert(client.id, NodeType::Client, 0.0);
+        }
+        for server in &config.server {
+            network.insert(server.id, NodeType::Server, 0.0);
+        }
+
+        for drone in &config.drone {
+            for neighbor in &drone.connected_node_ids {
+                network.link(drone.id, *neighbor);
+            }
+        }
+        for client in &config.client {
+            for neighbor in &client.connected_drone_ids {
+                network.link(client.id, *neighbor);
+            }
+        }
+        for server in &config.server {
+            for neighbor in &server.connected_drone_ids {
+                network.link(server.id, *neighbor);
+            }
+        }
+
+        network
+    }
+
+    fn insert(&mut self, id: NodeId, node_type: NodeType, pdr: f32) {
+        self.nodes.insert(
+            id,
+            MockNode {
+                node_type,
+                neighbors: Vec::new(),
+                pdr,
+            },
+        );
+    }
+
+    /// Ids of all nodes of the given type, sorted
+    #[must_use]
+    pub fn ids_of(&self, node_type: NodeType) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = self
+            .nodes
+            .iter()
+            .filter(|(_, node)| node.node_type == node_type)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[must_use]
+    pub fn node_type(&self, id: NodeId) -> Option<NodeType> {
+        self.nodes.get(&id).map(|node| node.node_type)
+    }
+
+    #[must_use]
+    pub fn pdr(&self, id: NodeId) -> f32 {
+        self.nodes.get(&id).map_or(0.0, |node| node.pdr)
+    }
+
+    /// Connect two nodes in both directions. Returns `false` if either node is unknown.
+    pub fn link(&mut self, a: NodeId, b: NodeId) -> bool {
+        if a == b || !self.nodes.contains_key(&a) || !self.nodes.contains_key(&b) {
+            return false;
+        }
+        for (from, to) in [(a, b), (b, a)] {
+            if let Some(node) = self.nodes.get_mut(&from) {
+                if !node.neighbors.contains(&to) {
+                    node.neighbors.push(to);
+                }
+            }
+        }
+        true
+    }
+
+    /// Disconnect two nodes in both directions. Returns `false` if they were not linked.
+    pub fn unlink(&mut self, a: NodeId, b: NodeId) -> bool {
+        let linked = self
+            .nodes
+            .get(&a)
+            .is_some_and(|node| node.neighbors.contains(&b));
+        for (from, to) in [(a, b), (b, a)] {
+            if let Some(node) = self.nodes.get_mut(&from) {
+                node.neighbors.retain(|&x| x != to);
+            }
+        }
+        linked
+    }
+
+    /// Add a drone and its links. Returns the neighbors it was actually linked to, or
+    /// `None` if the id is taken.
+    pub fn spawn(&mut self, id: NodeId, neighbors: &[NodeId], pdr: f32) -> Option<Vec<NodeId>> {
+        if self.nodes.contains_key(&id) {
+            return None;
+        }
+        self.insert(id, NodeType::Drone, pdr);
+        for neighbor in neighbors {
+            self.link(id, *neighbor);
+        }
+        self.nodes.get(&id).map(|node| node.neighbors.clone())
+    }
+
+    /// Remove a drone and all its links. Returns `false` if it is not a drone.
+    pub fn crash(&mut self, id: NodeId) -> bool {
+        if self.node_type(id) != Some(NodeType::Drone) {
+            return false;
+        }
+        if let Some(node) = self.nodes.remove(&id) {
+            for neighbor in node.neighbors {
+                if let Some(other) = self.nodes.get_mut(&neighbor) {
+                    other.neighbors.retain(|&x| x != id);
+                }
+            }
+        }
+        true
+    }
+
+    /// Shortest path from `src` to `dest` that only forwards through drones
+    #[must_use]
+    pub fn route(&self, src: NodeId, dest: NodeId) -> Option<Vec<NodeId>> {
+        let mut previous: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut visited = HashSet::from([src]);
+        let mut queue = VecDeque::from([src]);
+
+        while let Some(current) = queue.pop_front() {
+            if current == dest {
+                let mut path = vec![dest];
+                let mut node = dest;
+                while let Some(&prev) = previous.get(&node) {
+                    path.push(prev);
+                    node = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            // Only drones forward packets
+            if current != src && self.node_type(current) != Some(NodeType::Drone) {
+                continue;
+            }
+
+            for neighbor in self.nodes.get(&current).map_or(&[][..], |n| &n.neighbors) {
+                if visited.insert(*neighbor) {
+                    previous.insert(*neighbor, current);
+                    queue.push_back(*neighbor);
+                }
+            }
+        }
+
+        None
+    }
+}
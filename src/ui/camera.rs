@@ -0,0 +1,129 @@
+use eframe::egui::{self, emath::TSTransform, Pos2, Rect, Vec2};
+use wg_2024::network::NodeId;
+
+use crate::{
+    constants::{MAX_ZOOM, MIN_ZOOM, NODE_RADIUS},
+    logic::{nodes::NodeGUI, state::GUIState},
+    ui::network::NetworkVisualization,
+};
+
+/// A camera move requested outside of the canvas, applied once its size is known
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CameraRequest {
+    FitAll,
+    Focus(NodeId),
+}
+
+/// View of the network canvas, mapping the world coordinates node positions are stored in
+/// to screen coordinates
+#[derive(Clone, Debug)]
+pub struct Camera {
+    transform: TSTransform,
+    request: Option<CameraRequest>,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Camera {
+    /// A camera fitting the whole network as soon as it is drawn
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            transform: TSTransform::IDENTITY,
+            request: Some(CameraRequest::FitAll),
+        }
+    }
+
+    #[must_use]
+    pub fn zoom(&self) -> f32 {
+        self.transform.scaling
+    }
+
+    #[must_use]
+    pub fn to_screen(&self, world: Pos2) -> Pos2 {
+        self.transform.mul_pos(world)
+    }
+
+    #[must_use]
+    pub fn to_world(&self, screen: Pos2) -> Pos2 {
+        self.transform.inverse().mul_pos(screen)
+    }
+
+    /// Radius nodes are drawn with, growing with the zoom but kept readable
+    #[must_use]
+    pub fn node_radius(&self) -> f32 {
+        (NODE_RADIUS * self.zoom()).clamp(NODE_RADIUS / 2.0, NODE_RADIUS * 3.0)
+    }
+
+    /// Move the view by `delta` screen pixels
+    pub fn pan(&mut self, delta: Vec2) {
+        self.transform.translation += delta;
+    }
+
+    /// Zoom by `factor`, keeping the world point under `anchor` in place
+    pub fn zoom_at(&mut self, anchor: Pos2, factor: f32) {
+        let world = self.to_world(anchor);
+        self.transform.scaling = (self.zoom() * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.transform.translation += anchor - self.to_screen(world);
+    }
+
+    /// Fit every node in the canvas the next time it is drawn
+    pub fn fit_all(&mut self) {
+        self.request = Some(CameraRequest::FitAll);
+    }
+
+    /// Center `node` in the canvas the next time it is drawn
+    pub fn focus(&mut self, node: NodeId) {
+        self.request = Some(CameraRequest::Focus(node));
+    }
+
+    /// Apply the pending request, if any, to the screen area `canvas`
+    pub fn apply_request(&mut self, canvas: Rect, state: &GUIState) {
+        match self.request {
+            Some(CameraRequest::FitAll) => {
+                let positions: Vec<Pos2> = state.nodes.values().map(NodeGUI::position).collect();
+                // Empty until the topology arrives, keep the request until then
+                let bounds = Rect::from_points(&positions);
+                if bounds.is_finite() {
+                    self.fit(canvas, bounds.expand(NODE_RADIUS * 2.0));
+                    self.request = None;
+                }
+            }
+            Some(CameraRequest::Focus(node)) => {
+                if let Some(world) = state.nodes.get(&node).map(NodeGUI::position) {
+                    let zoom = self.zoom().max(1.0);
+                    self.transform = TSTransform::new(canvas.center() - world * zoom, zoom);
+                }
+                self.request = None;
+            }
+            None => {}
+        }
+    }
+
+    fn fit(&mut self, canvas: Rect, bounds: Rect) {
+        let zoom = (canvas.width() / bounds.width())
+            .min(canvas.height() / bounds.height())
+            .clamp(MIN_ZOOM, MAX_ZOOM);
+        let translation = canvas.center() - bounds.center() * zoom;
+        self.transform = TSTransform::new(translation, zoom);
+    }
+}
+
+impl NetworkVisualization {
+    /// Buttons moving the camera, shown in the toolbar
+    pub fn render_camera_controls(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .button("Fit all")
+            .on_hover_text("Show the whole network")
+            .clicked()
+        {
+            self.camera.fit_all();
+        }
+        ui.label(format!("{:.0}%", self.camera.zoom() * 100.0))
+            .on_hover_text("Scroll to zoom, drag the background to pan");
+    }
+}
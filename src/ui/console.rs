@@ -0,0 +1,117 @@
+use eframe::egui::{
+    self,
+    text::{CCursor, CCursorRange},
+    Color32, Key,
+};
+
+use crate::logic::{
+    console::{Console, LineKind},
+    state::GUIState,
+};
+
+/// Keyboard console issuing commands with a small command language
+pub struct ConsolePanel {
+    pub open: bool,
+    console: Console,
+}
+
+impl Default for ConsolePanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConsolePanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            console: Console::new(),
+        }
+    }
+
+    pub fn render(&mut self, state: &mut GUIState, ctx: &egui::Context) {
+        let mut open = self.open;
+
+        egui::Window::new("Console")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                self.render_output(ui);
+                ui.separator();
+                self.render_input(state, ui);
+            });
+
+        self.open = open;
+    }
+
+    fn render_output(&self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical()
+            .max_height(250.0)
+            .stick_to_bottom(true)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for line in self.console.output() {
+                    let color = match line.kind {
+                        LineKind::Input => Color32::LIGHT_GRAY,
+                        LineKind::Info => Color32::LIGHT_GREEN,
+                        LineKind::Error => Color32::LIGHT_RED,
+                    };
+                    ui.label(egui::RichText::new(&line.text).monospace().color(color));
+                }
+            });
+    }
+
+    fn render_input(&mut self, state: &mut GUIState, ui: &mut egui::Ui) {
+        let id = ui.make_persistent_id("console_input");
+        let mut output = egui::TextEdit::singleline(&mut self.console.input)
+            .id(id)
+            .lock_focus(true)
+            .font(egui::TextStyle::Monospace)
+            .hint_text("type `help` for the list of commands")
+            .desired_width(f32::INFINITY)
+            .show(ui);
+
+        let mut move_cursor_to_end = false;
+
+        // With the focus locked, Tab is typed into the input: use it to autocomplete
+        if self.console.input.contains('\t') {
+            self.console.input = self.console.input.replace('\t', "");
+            self.console.complete(state);
+            move_cursor_to_end = true;
+        }
+
+        if output.response.has_focus() {
+            if ui.input(|i| i.key_pressed(Key::ArrowUp)) {
+                self.console.history_previous();
+                move_cursor_to_end = true;
+            } else if ui.input(|i| i.key_pressed(Key::ArrowDown)) {
+                self.console.history_next();
+                move_cursor_to_end = true;
+            }
+        }
+
+        if output.response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+            self.console.submit(state);
+            output.response.request_focus();
+        }
+
+        if move_cursor_to_end {
+            let end = CCursor::new(self.console.input.chars().count());
+            output
+                .state
+                .cursor
+                .set_char_range(Some(CCursorRange::one(end)));
+            output.state.store(ui.ctx(), id);
+        }
+
+        if !self.console.suggestions.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                for suggestion in &self.console.suggestions {
+                    ui.weak(suggestion);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use eframe::egui::{self, Color32, Pos2, Stroke, Vec2};
+
+use crate::{
+    constants::METRICS_HISTORY_SECS,
+    logic::{animation::kind_color, metrics::MetricsSample, state::GUIState, traffic::PacketKind},
+};
+
+/// Height of each chart of the dashboard
+const CHART_HEIGHT: f32 = 100.0;
+
+/// A line drawn in a chart, one value per second
+struct Series {
+    label: String,
+    color: Color32,
+    values: Vec<f32>,
+}
+
+/// Panel plotting the network metrics over time
+pub struct DashboardPanel {
+    pub open: bool,
+    /// Seconds shown in the charts
+    window_secs: usize,
+    /// Samples frozen when the dashboard was paused
+    frozen: Option<Vec<MetricsSample>>,
+}
+
+impl Default for DashboardPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DashboardPanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            window_secs: 60,
+            frozen: None,
+        }
+    }
+
+    pub fn render(&mut self, state: &GUIState, ctx: &egui::Context) {
+        let mut open = self.open;
+
+        egui::Window::new("Dashboard")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                self.render_controls(state, ui);
+
+                ui.separator();
+
+                let samples = self.samples(state);
+                Self::render_charts(&samples, self.window_secs, ui);
+            });
+
+        self.open = open;
+
+        // Keep the charts scrolling while no events arrive
+        if self.open && self.frozen.is_none() {
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        }
+    }
+
+    fn render_controls(&mut self, state: &GUIState, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Window:");
+            ui.add(
+                egui::Slider::new(&mut self.window_secs, 10..=METRICS_HISTORY_SECS)
+                    .logarithmic(true)
+                    .suffix("s"),
+            );
+
+            let label = if self.frozen.is_some() {
+                "Resume"
+            } else {
+                "Pause"
+            };
+            if ui.button(label).clicked() {
+                self.frozen = match self.frozen {
+                    Some(_) => None,
+                    None => Some(state.metrics.samples().copied().collect()),
+                };
+            }
+        });
+    }
+
+    /// The last `window_secs` completed seconds, oldest first
+    fn samples(&self, state: &GUIState) -> Vec<MetricsSample> {
+        let mut samples: Vec<MetricsSample> = match &self.frozen {
+            Some(frozen) => frozen
+                .iter()
+                .rev()
+                .take(self.window_secs)
+                .copied()
+                .collect(),
+            None => state
+                .metrics
+                .samples()
+                .rev()
+                .take(self.window_secs)
+                .copied()
+                .collect(),
+        };
+        samples.reverse();
+        samples
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn render_charts(samples: &[MetricsSample], window_secs: usize, ui: &mut egui::Ui) {
+        let values = |value: fn(&MetricsSample) -> f32| samples.iter().map(value).collect();
+
+        ui.label("Packets sent per second");
+        let throughput: Vec<Series> = PacketKind::ALL
+            .iter()
+            .map(|kind| Series {
+                label: format!("{kind:?}"),
+                color: kind_color(*kind),
+                values: samples
+                    .iter()
+                    .map(|sample| sample.sent(*kind) as f32)
+                    .collect(),
+            })
+            .collect();
+        Self::render_chart(ui, &throughput, window_secs);
+
+        ui.label("Packets dropped per second");
+        Self::render_chart(
+            ui,
+            &[Series {
+                label: "Dropped".to_string(),
+                color: Color32::LIGHT_RED,
+                values: values(|sample| sample.dropped as f32),
+            }],
+            window_secs,
+        );
+
+        ui.label("Network size");
+        Self::render_chart(
+            ui,
+            &[
+                Series {
+                    label: "Active nodes".to_string(),
+                    color: Color32::LIGHT_GREEN,
+                    values: values(|sample| sample.active_nodes as f32),
+                },
+                Series {
+                    label: "Crashed drones".to_string(),
+                    color: Color32::LIGHT_RED,
+                    values: values(|sample| sample.crashed_drones as f32),
+                },
+            ],
+            window_secs,
+        );
+    }
+
+    /// Plot the series right-aligned in a window of `window_secs` seconds, with a legend
+    /// showing their latest values and a tooltip with the values under the pointer
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn render_chart(ui: &mut egui::Ui, series: &[Series], window_secs: usize) {
+        let size = Vec2::new(ui.available_width().max(200.0), CHART_HEIGHT);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        let peak = series
+            .iter()
+            .flat_map(|line| line.values.iter().copied())
+            .fold(0.0, f32::max);
+        let max = peak.max(1.0);
+
+        let step = rect.width() / window_secs.saturating_sub(1).max(1) as f32;
+        let point = |len: usize, i: usize, value: f32| {
+            let x = rect.right() - step * (len - 1 - i) as f32;
+            let y = rect.bottom() - rect.height() * value / max;
+            Pos2::new(x, y)
+        };
+
+        for line in series {
+            let points: Vec<Pos2> = line
+                .values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| point(line.values.len(), i, *value))
+                .collect();
+            painter.add(egui::Shape::line(points, Stroke::new(1.5, line.color)));
+        }
+
+        painter.text(
+            rect.left_top() + Vec2::new(4.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            format!("{peak:.0}"),
+            egui::FontId::monospace(10.0),
+            ui.visuals().weak_text_color(),
+        );
+
+        ui.horizontal_wrapped(|ui| {
+            for line in series {
+                let latest = line.values.last().copied().unwrap_or(0.0);
+                ui.colored_label(line.color, format!("{}: {latest:.0}", line.label));
+            }
+        });
+
+        // Show the values of the second under the pointer
+        let len = series.first().map_or(0, |line| line.values.len());
+        if let Some(pointer) = response.hover_pos().filter(|_| len > 0) {
+            // The pointer is inside the chart, so this is never negative
+            let seconds_ago = ((rect.right() - pointer.x) / step).round() as usize;
+            if seconds_ago < len {
+                let i = len - 1 - seconds_ago;
+                let x = point(len, i, 0.0).x;
+                painter.vline(x, rect.y_range(), Stroke::new(1.0, Color32::DARK_GRAY));
+
+                let text = series
+                    .iter()
+                    .map(|line| format!("{}: {:.0}", line.label, line.values[i]))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                response.on_hover_text(format!("{}s ago\n{text}", seconds_ago + 1));
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use eframe::egui::{self, Color32};
+
+use crate::logic::state::GUIState;
+
+/// Dismissible panel listing the errors raised while handling events and commands
+pub struct ErrorPanel;
+
+impl Default for ErrorPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorPanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&mut self, state: &mut GUIState, ctx: &egui::Context) {
+        if state.errors.is_empty() {
+            return;
+        }
+
+        let mut dismissed = None;
+        let mut dismiss_all = false;
+
+        egui::Window::new(format!("Errors ({})", state.errors.len()))
+            .resizable(true)
+            .collapsible(true)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        for (index, record) in state.errors.iter().enumerate().rev() {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("Dismiss").clicked() {
+                                    dismissed = Some(index);
+                                }
+
+                                let text = if record.occurrences > 1 {
+                                    format!("{} (x{})", record.error, record.occurrences)
+                                } else {
+                                    record.error.to_string()
+                                };
+                                ui.colored_label(Color32::LIGHT_RED, text);
+                                ui.weak(format!("{}s ago", record.last_seen.elapsed().as_secs()));
+                            });
+                        }
+                    });
+
+                ui.add_space(5.0);
+
+                if ui.button("Dismiss all").clicked() {
+                    dismiss_all = true;
+                }
+            });
+
+        if dismiss_all {
+            state.errors.clear();
+        } else if let Some(index) = dismissed {
+            state.dismiss_error(index);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use eframe::egui::{self, Color32, Pos2, Stroke, Vec2};
+use wg_2024::network::NodeId;
+
+use crate::{
+    constants::FLOOD_WAVE_SECS,
+    logic::{floods::Flood, state::GUIState},
+    ui::{camera::Camera, network::NetworkVisualization},
+};
+
+/// Number of floods listed in the flood panel
+const LISTED_FLOODS: usize = 30;
+
+/// Panel listing recent network discoveries, the selected one has its tree drawn on the
+/// canvas
+pub struct FloodPanel;
+
+impl Default for FloodPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FloodPanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&mut self, state: &mut GUIState, ctx: &egui::Context) {
+        let mut open = state.show_floods;
+
+        egui::Window::new("Floods")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} floods seen", state.floods.len()));
+                    if ui.button("Clear").clicked() {
+                        state.floods.clear();
+                    }
+                });
+
+                ui.separator();
+
+                Self::render_floods(state, ui);
+
+                ui.separator();
+
+                match state.floods.selected() {
+                    Some(flood) => Self::render_responses(flood, ui),
+                    None => {
+                        ui.label("Select a flood to draw its tree and responses");
+                    }
+                }
+            });
+
+        state.show_floods = open;
+    }
+
+    fn render_floods(state: &mut GUIState, ui: &mut egui::Ui) {
+        let mut selected = state.floods.selected;
+        let total = state.nodes.len();
+
+        egui::ScrollArea::vertical()
+            .id_salt("floods")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                for (key, flood) in state.floods.recent().into_iter().take(LISTED_FLOODS) {
+                    let text = format!(
+                        "Flood {} from {}: {}/{} nodes, {} hops, {:.0}ms, {} responses",
+                        key.flood_id,
+                        key.initiator,
+                        flood.reached.len(),
+                        total,
+                        flood.depth(),
+                        flood.duration().as_secs_f32() * 1000.0,
+                        flood.responses.len()
+                    );
+                    if ui.selectable_label(selected == Some(key), text).clicked() {
+                        selected = (selected != Some(key)).then_some(key);
+                    }
+                }
+            });
+
+        state.floods.selected = selected;
+    }
+
+    fn render_responses(flood: &Flood, ui: &mut egui::Ui) {
+        ui.label(format!(
+            "{} responses, {} back at the initiator",
+            flood.responses.len(),
+            flood.returned_responses()
+        ));
+
+        egui::ScrollArea::vertical()
+            .id_salt("flood_responses")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for response in &flood.responses {
+                    let text = Self::path_text(&response.path);
+                    if response.returned {
+                        ui.colored_label(Color32::LIGHT_GREEN, text);
+                    } else {
+                        ui.colored_label(Color32::LIGHT_GRAY, format!("{text} (in flight)"));
+                    }
+                }
+            });
+    }
+
+    fn path_text(path: &[NodeId]) -> String {
+        path.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+}
+
+impl NetworkVisualization {
+    /// Draw a wave spreading from each node a flood request just reached, and the tree and
+    /// responses of the selected flood
+    pub(super) fn draw_floods(painter: &egui::Painter, state: &GUIState, camera: &Camera) {
+        let screen = |id: &NodeId| {
+            state
+                .nodes
+                .get(id)
+                .map(|node| camera.to_screen(node.position()))
+        };
+        let radius = camera.node_radius();
+
+        if let Some(flood) = state.floods.selected() {
+            for (parent, child) in flood.tree() {
+                if let (Some(start), Some(end)) = (screen(&parent), screen(&child)) {
+                    painter.arrow(
+                        start,
+                        (end - start) * (1.0 - radius / start.distance(end).max(radius)),
+                        Stroke::new(3.0, Color32::WHITE),
+                    );
+                }
+            }
+
+            for response in &flood.responses {
+                let points: Vec<Pos2> = response.path.iter().filter_map(screen).collect();
+                let color = if response.returned {
+                    Color32::LIGHT_GREEN
+                } else {
+                    Color32::LIGHT_GRAY
+                };
+                painter.extend(egui::Shape::dashed_line(
+                    &points,
+                    Stroke::new(1.5, color.gamma_multiply(0.6)),
+                    6.0,
+                    6.0,
+                ));
+            }
+
+            for (id, reach) in &flood.reached {
+                if let Some(center) = screen(id) {
+                    painter.text(
+                        center + Vec2::new(radius, radius),
+                        egui::Align2::LEFT_TOP,
+                        reach.depth.to_string(),
+                        egui::FontId::proportional(12.0),
+                        Color32::WHITE,
+                    );
+                }
+            }
+        }
+
+        // Rings expanding from the nodes as the request reaches them, and the link it came
+        // through lighting up
+        for (_, flood) in state.floods.recent() {
+            for (id, reach) in &flood.reached {
+                let age = reach.at.elapsed().as_secs_f32() / FLOOD_WAVE_SECS;
+                if age >= 1.0 {
+                    continue;
+                }
+                let Some(center) = screen(id) else {
+                    continue;
+                };
+                let color = Color32::WHITE.gamma_multiply(1.0 - age);
+                painter.circle_stroke(center, radius * (1.0 + 2.0 * age), Stroke::new(2.0, color));
+                if let Some(start) = reach.parent.as_ref().and_then(screen) {
+                    painter.line_segment([start, center], Stroke::new(3.0, color));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use eframe::egui::{self, Color32, Stroke, Vec2};
+use wg_2024::network::NodeId;
+
+use crate::{
+    constants::{EDGE_COLOR, HEATMAP_MAX_WIDTH, LEGEND_X_START},
+    logic::state::GUIState,
+    ui::{camera::Camera, network::NetworkVisualization},
+};
+
+impl NetworkVisualization {
+    pub(super) fn draw_connections(painter: &egui::Painter, state: &GUIState, camera: &Camera) {
+        let rates: Vec<(NodeId, NodeId, f32)> = state
+            .graph
+            .edges()
+            .map(|(a, b)| (a, b, state.traffic.edge_rate(a, b)))
+            .collect();
+        let max_rate = rates.iter().map(|(_, _, rate)| *rate).fold(0.0, f32::max);
+
+        for (start_id, end_id, rate) in rates {
+            if let (Some(start_node), Some(end_node)) =
+                (state.nodes.get(&start_id), state.nodes.get(&end_id))
+            {
+                let stroke = if state.show_heatmap && max_rate > 0.0 {
+                    let heat = rate / max_rate;
+                    Stroke::new(
+                        2.0 + heat * (HEATMAP_MAX_WIDTH - 2.0),
+                        Self::heat_color(heat),
+                    )
+                } else {
+                    Stroke::new(2.0, EDGE_COLOR)
+                };
+
+                painter.line_segment(
+                    [
+                        camera.to_screen(start_node.position()),
+                        camera.to_screen(end_node.position()),
+                    ],
+                    stroke,
+                );
+            }
+        }
+
+        if state.show_heatmap {
+            Self::draw_heatmap_scale(painter, max_rate);
+        }
+    }
+
+    /// Color of a link carrying `heat` times the busiest link's traffic
+    fn heat_color(heat: f32) -> Color32 {
+        if heat < 0.5 {
+            EDGE_COLOR.lerp_to_gamma(Color32::YELLOW, heat * 2.0)
+        } else {
+            Color32::YELLOW.lerp_to_gamma(Color32::RED, (heat - 0.5) * 2.0)
+        }
+    }
+
+    fn draw_heatmap_scale(painter: &egui::Painter, max_rate: f32) {
+        let steps = 20;
+        let origin = painter.clip_rect().left_bottom() + Vec2::new(LEGEND_X_START, -30.0);
+        let step_width = 6.0;
+
+        for step in 0..=steps {
+            #[allow(clippy::cast_precision_loss)]
+            let heat = step as f32 / steps as f32;
+            #[allow(clippy::cast_precision_loss)]
+            let x = origin.x + step as f32 * step_width;
+            painter.line_segment(
+                [
+                    egui::pos2(x, origin.y),
+                    egui::pos2(x + step_width, origin.y),
+                ],
+                Stroke::new(
+                    2.0 + heat * (HEATMAP_MAX_WIDTH - 2.0),
+                    Self::heat_color(heat),
+                ),
+            );
+        }
+
+        let font = egui::FontId::proportional(11.0);
+        painter.text(
+            origin + Vec2::new(0.0, 8.0),
+            egui::Align2::LEFT_TOP,
+            "0",
+            font.clone(),
+            Color32::LIGHT_GRAY,
+        );
+        #[allow(clippy::cast_precision_loss)]
+        painter.text(
+            origin + Vec2::new((steps + 1) as f32 * step_width, 8.0),
+            egui::Align2::RIGHT_TOP,
+            format!("{max_rate:.1} packets/s"),
+            font,
+            Color32::LIGHT_GRAY,
+        );
+    }
+}
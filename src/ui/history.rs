@@ -0,0 +1,78 @@
+use eframe::egui::{self, Color32, Key, KeyboardShortcut, Modifiers};
+
+use crate::logic::{actions, history::Edit, state::GUIState};
+
+const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+const REDO: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+
+/// Undo/redo buttons and their Ctrl+Z / Ctrl+Shift+Z shortcuts
+pub struct HistoryControls;
+
+impl HistoryControls {
+    /// Undo or redo when a shortcut is pressed, unless a text field has focus
+    pub fn handle_shortcuts(state: &mut GUIState, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        // Redo first: Ctrl+Shift+Z would also match the undo shortcut
+        let (redo, undo) =
+            ctx.input_mut(|i| (i.consume_shortcut(&REDO), i.consume_shortcut(&UNDO)));
+
+        if redo {
+            let result = actions::redo(state);
+            state.report(result);
+        } else if undo {
+            let result = actions::undo(state);
+            state.report(result);
+        }
+    }
+
+    pub fn render(state: &mut GUIState, ui: &mut egui::Ui) {
+        let next_undo = state.history.next_undo().cloned();
+        let next_redo = state.history.next_redo().cloned();
+
+        let undo = ui
+            .add_enabled(next_undo.is_some(), egui::Button::new("Undo"))
+            .on_hover_text(Self::describe(next_undo.as_ref(), "undo", UNDO, ui.ctx()))
+            .on_disabled_hover_text(Self::describe(next_undo.as_ref(), "undo", UNDO, ui.ctx()));
+        if undo.clicked() {
+            let result = actions::undo(state);
+            state.report(result);
+        }
+
+        let redo = ui
+            .add_enabled(next_redo.is_some(), egui::Button::new("Redo"))
+            .on_hover_text(Self::describe(next_redo.as_ref(), "redo", REDO, ui.ctx()))
+            .on_disabled_hover_text(Self::describe(next_redo.as_ref(), "redo", REDO, ui.ctx()));
+        if redo.clicked() {
+            let result = actions::redo(state);
+            state.report(result);
+        }
+
+        // Undo drops the latest edit when it cannot be reverted, and reverts the one before
+        if let Some(edit) = next_undo.filter(|edit| !edit.is_reversible()) {
+            ui.colored_label(
+                Color32::LIGHT_RED,
+                format!("Irreversible, undo skips it: {edit}"),
+            );
+        }
+    }
+
+    fn describe(
+        edit: Option<&Edit>,
+        verb: &str,
+        shortcut: KeyboardShortcut,
+        ctx: &egui::Context,
+    ) -> String {
+        let shortcut = ctx.format_shortcut(&shortcut);
+        match edit {
+            Some(edit) if edit.is_reversible() => format!("{edit} ({shortcut})"),
+            Some(edit) => {
+                format!("{edit} cannot be undone, skip it and undo the edit before ({shortcut})")
+            }
+            None => format!("Nothing to {verb} ({shortcut})"),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use eframe::egui::{self, Pos2, Rect};
+use wg_2024::network::NodeId;
+
+use crate::{
+    constants::PACKET_MARKER_RADIUS,
+    logic::{routes::TraceKey, state::GUIState},
+    ui::network::NetworkVisualization,
+};
+
+/// Zoom factor per pixel scrolled with the mouse wheel
+const SCROLL_ZOOM_SPEED: f32 = 0.002;
+/// Distance in pixels within which the pointer hovers a link
+const EDGE_HOVER_DISTANCE: f32 = 5.0;
+
+impl NetworkVisualization {
+    pub(super) fn handle_interactions(
+        &mut self,
+        ui: &egui::Ui,
+        state: &mut GUIState,
+        response: &egui::Response,
+    ) {
+        let shift = ui.input(|i| i.modifiers.shift);
+
+        // Open the window of the clicked node, or add it to the selection with shift, trace
+        // the session of the clicked packet, or clear the selection
+        if let Some(pos) = response
+            .interact_pointer_pos()
+            .filter(|_| response.clicked())
+        {
+            let node = self.get_node_at_position(state, self.camera.to_world(pos));
+            let packet = state
+                .show_trace
+                .then(|| self.get_packet_at_position(state, pos))
+                .flatten();
+            match (node, packet) {
+                (Some(id), _) if shift => state.selection.toggle(id),
+                (Some(id), _) => {
+                    if let Some(node) = state.nodes.get_mut(&id) {
+                        node.selected = true;
+                    }
+                }
+                (None, Some(key)) => state.routes.selected = Some(key),
+                (None, None) if !shift => state.selection.clear(),
+                (None, None) => {}
+            }
+        }
+
+        // Drag the node under the pointer, select the nodes in a rectangle when the drag
+        // starts on empty space with shift, or pan otherwise
+        if response.drag_started() {
+            let pos = response.interact_pointer_pos();
+            self.dragged_node =
+                pos.and_then(|pos| self.get_node_at_position(state, self.camera.to_world(pos)));
+            self.rubber_band = pos
+                .filter(|_| shift && self.dragged_node.is_none())
+                .map(|pos| (pos, pos));
+            // The user places the node now, stop moving it to the layout
+            if self.dragged_node.is_some() {
+                state.layout_transition = None;
+            }
+        }
+        if response.dragged() {
+            let delta = response.drag_delta();
+            match self.dragged_node.and_then(|id| state.nodes.get_mut(&id)) {
+                Some(node) => {
+                    node.x += delta.x / self.camera.zoom();
+                    node.y += delta.y / self.camera.zoom();
+                    if let Some(live) = &state.live_layout {
+                        live.pin(node.id, Some(node.position()));
+                    }
+                }
+                None => match &mut self.rubber_band {
+                    Some((_, end)) => *end += delta,
+                    None => self.camera.pan(delta),
+                },
+            }
+        }
+        if response.drag_stopped() {
+            if let (Some(id), Some(live)) = (self.dragged_node, &state.live_layout) {
+                live.pin(id, None);
+            }
+            if let Some((start, end)) = self.rubber_band.take() {
+                let rect = Rect::from_two_pos(start, end);
+                let inside: Vec<NodeId> = state
+                    .nodes
+                    .values()
+                    .filter(|node| rect.contains(self.camera.to_screen(node.position())))
+                    .map(|node| node.id)
+                    .collect();
+                state.selection.extend(inside);
+            }
+            self.dragged_node = None;
+        }
+
+        // Zoom around the pointer with the mouse wheel or a pinch
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = pinch * (scroll * SCROLL_ZOOM_SPEED).exp();
+            if (factor - 1.0).abs() > f32::EPSILON {
+                self.camera.zoom_at(pointer, factor);
+            }
+        }
+    }
+
+    /// Get the node drawn at a position in world coordinates, the closest one if they overlap
+    #[must_use]
+    pub fn get_node_at_position(&self, state: &GUIState, pos: Pos2) -> Option<NodeId> {
+        let radius = self.camera.node_radius() / self.camera.zoom();
+        state
+            .nodes
+            .iter()
+            .map(|(id, node)| (*id, pos.distance(node.position())))
+            .filter(|(_, distance)| *distance <= radius)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id)
+    }
+
+    /// Get the link drawn at a position in screen coordinates, the closest one if several are
+    /// within reach
+    #[must_use]
+    pub fn get_edge_at_position(&self, state: &GUIState, pos: Pos2) -> Option<(NodeId, NodeId)> {
+        state
+            .graph
+            .edges()
+            .filter_map(|(a, b)| {
+                let start = self.camera.to_screen(state.nodes.get(&a)?.position());
+                let end = self.camera.to_screen(state.nodes.get(&b)?.position());
+                let along = end - start;
+                let t = if along.length_sq() > 0.0 {
+                    ((pos - start).dot(along) / along.length_sq()).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                Some(((a, b), pos.distance(start + along * t)))
+            })
+            .filter(|(_, distance)| *distance <= EDGE_HOVER_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(edge, _)| edge)
+    }
+
+    /// Get the session of the packet drawn at a position in screen coordinates
+    #[must_use]
+    pub fn get_packet_at_position(&self, state: &GUIState, pos: Pos2) -> Option<TraceKey> {
+        state
+            .animations
+            .markers()
+            .filter(|marker| marker.burst().is_none())
+            .filter_map(|marker| {
+                let src = self
+                    .camera
+                    .to_screen(state.nodes.get(&marker.src)?.position());
+                let dest = self
+                    .camera
+                    .to_screen(state.nodes.get(&marker.dest)?.position());
+                let distance = pos.distance(src.lerp(dest, marker.position()));
+                Some((marker.trace?, distance))
+            })
+            .filter(|(_, distance)| *distance <= PACKET_MARKER_RADIUS * 2.0)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(key, _)| key)
+    }
+}
@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use eframe::egui::{self, Color32};
+
+use crate::logic::{error::GuiError, journal::CommandOutcome, GuiCore};
+
+/// Panel listing every issued command, with export and script re-execution
+pub struct JournalPanel {
+    pub open: bool,
+    export_path: String,
+    script_path: String,
+}
+
+impl Default for JournalPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JournalPanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            export_path: "commands.jsonl".to_string(),
+            script_path: "commands.jsonl".to_string(),
+        }
+    }
+
+    pub fn render(&mut self, core: &mut GuiCore, ctx: &egui::Context) {
+        let mut open = self.open;
+
+        egui::Window::new("Command Journal")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(450.0)
+            .show(ctx, |ui| {
+                Self::render_entries(core, ui);
+
+                ui.separator();
+
+                Self::render_timeout(core, ui);
+
+                self.render_export(core, ui);
+                self.render_script(core, ui);
+            });
+
+        self.open = open;
+    }
+
+    fn render_entries(core: &GuiCore, ui: &mut egui::Ui) {
+        let entries = core.state.journal.entries();
+        ui.label(format!("{} commands issued", entries.len()));
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, entries.len(), |ui, rows| {
+                for entry in &entries[rows] {
+                    ui.horizontal(|ui| {
+                        #[allow(clippy::cast_precision_loss)]
+                        ui.monospace(format!("{:>8.2}s", entry.elapsed_ms as f64 / 1000.0));
+                        ui.label(format!("{:?}", entry.command));
+                        match &entry.outcome {
+                            CommandOutcome::Sent => {
+                                ui.colored_label(Color32::LIGHT_BLUE, "sent");
+                            }
+                            CommandOutcome::Confirmed => {
+                                ui.colored_label(Color32::LIGHT_GREEN, "confirmed");
+                            }
+                            CommandOutcome::TimedOut => {
+                                ui.colored_label(Color32::LIGHT_RED, "not confirmed");
+                            }
+                            CommandOutcome::Unconfirmed => {
+                                ui.colored_label(Color32::GRAY, "unconfirmed");
+                            }
+                            CommandOutcome::Failed(reason) => {
+                                ui.colored_label(Color32::LIGHT_RED, format!("failed: {reason}"));
+                            }
+                        }
+                    });
+                }
+            });
+    }
+
+    fn render_timeout(core: &mut GuiCore, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} commands waiting for confirmation, timeout:",
+                core.state.pending.commands().len()
+            ));
+
+            let mut secs = core.state.pending.timeout.as_secs_f32();
+            let drag = egui::DragValue::new(&mut secs)
+                .range(0.5..=60.0)
+                .speed(0.1)
+                .suffix("s");
+            if ui.add(drag).changed() {
+                core.state.pending.timeout = Duration::from_secs_f32(secs);
+            }
+        });
+    }
+
+    fn render_export(&mut self, core: &mut GuiCore, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Export to:");
+            ui.text_edit_singleline(&mut self.export_path);
+
+            if ui.button("Export").clicked() {
+                let result = core.state.journal.export(&self.export_path).map(|_| ());
+                core.state.report(result);
+            }
+
+            if ui.button("Clear").clicked() {
+                core.state.journal.clear();
+            }
+        });
+    }
+
+    fn render_script(&mut self, core: &mut GuiCore, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if let Some(script) = core.script_state() {
+                ui.label(format!(
+                    "Running script: {}/{} commands issued",
+                    script.position(),
+                    script.len()
+                ));
+                return;
+            }
+
+            ui.label("Run script:");
+            ui.text_edit_singleline(&mut self.script_path);
+
+            if ui.button("Run").clicked() {
+                let result = core.run_script(&self.script_path).and_then(|len| {
+                    if len == 0 {
+                        Err(GuiError::InvalidInput("The script is empty".to_string()))
+                    } else {
+                        Ok(())
+                    }
+                });
+                core.state.report(result);
+            }
+        });
+    }
+}
@@ -0,0 +1,119 @@
+use eframe::egui;
+use wg_2024::network::NodeId;
+
+use crate::{
+    logic::{
+        layout::{self, Layout},
+        positions::SavedPositions,
+        state::GUIState,
+    },
+    ui::network::NetworkVisualization,
+};
+
+impl NetworkVisualization {
+    /// Menu switching the layout of the nodes, with the layout seed and saved positions
+    pub fn render_layout_menu(&mut self, state: &mut GUIState, ui: &mut egui::Ui) {
+        ui.menu_button(format!("Layout: {}", state.layout.name()), |ui| {
+            let mut selected = None;
+
+            for layout in [
+                Layout::ForceDirected,
+                Layout::Circle,
+                Layout::Layered,
+                Layout::Grid,
+            ] {
+                if ui.button(layout.name()).clicked() {
+                    selected = Some(layout);
+                }
+            }
+
+            ui.menu_button("Shortest-path tree from", |ui| {
+                let mut ids: Vec<NodeId> = state.nodes.keys().copied().collect();
+                ids.sort_unstable();
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for id in ids {
+                            if ui.button(format!("Node {id}")).clicked() {
+                                selected = Some(Layout::ShortestPathTree(id));
+                            }
+                        }
+                    });
+            });
+
+            if ui
+                .add_enabled(
+                    state.saved_positions.is_some(),
+                    egui::Button::new(Layout::Saved.name()),
+                )
+                .clicked()
+            {
+                selected = Some(Layout::Saved);
+            }
+
+            ui.separator();
+
+            Self::render_layout_seed(state, ui);
+            self.render_saved_positions(state, ui);
+
+            if let Some(layout) = selected {
+                let result = layout::apply(state, layout);
+                state.report(result);
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn render_layout_seed(state: &mut GUIState, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut seeded = state.layout_seed.is_some();
+            if ui
+                .checkbox(&mut seeded, "Seed")
+                .on_hover_text("Start the force-directed layout from the same positions every time")
+                .changed()
+            {
+                state.layout_seed = seeded.then_some(0);
+            }
+            if let Some(seed) = &mut state.layout_seed {
+                ui.add(egui::DragValue::new(seed));
+            }
+        });
+    }
+
+    fn render_saved_positions(&mut self, state: &mut GUIState, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Positions:");
+            ui.text_edit_singleline(&mut self.positions_path);
+
+            if ui.button("Save").clicked() {
+                let saved = SavedPositions::capture(&state.nodes, state.canvas_size);
+                let result = saved.save(&self.positions_path);
+                if result.is_ok() {
+                    state.saved_positions = Some(saved);
+                }
+                state.report(result);
+            }
+
+            if ui.button("Load").clicked() {
+                match SavedPositions::load(&self.positions_path) {
+                    Ok(saved) => {
+                        let matches = saved.matches(&state.graph);
+                        state.saved_positions = Some(saved);
+                        // Otherwise they are applied when their topology is loaded
+                        if matches {
+                            let result = layout::apply(state, Layout::Saved);
+                            state.report(result);
+                        }
+                    }
+                    Err(e) => state.record_error(e),
+                }
+            }
+        });
+
+        if let Some(saved) = &state.saved_positions {
+            if !saved.matches(&state.graph) {
+                ui.weak("The loaded positions are for another topology");
+            }
+        }
+    }
+}
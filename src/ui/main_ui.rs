@@ -1,6 +1,11 @@
 use crate::{
-    logic::state::GUIState,
-    ui::{legend::Legend, network::NetworkVisualization, spawn::SpawnPanel},
+    logic::{state::GUIState, GuiCore},
+    ui::{
+        console::ConsolePanel, dashboard::DashboardPanel, errors::ErrorPanel, floods::FloodPanel,
+        history::HistoryControls, journal::JournalPanel, legend::Legend,
+        network::NetworkVisualization, recording::RecordingPanel, selection::SelectionPanel,
+        spawn::SpawnPanel, trace::TracePanel,
+    },
 };
 use eframe::egui;
 
@@ -9,6 +14,14 @@ pub struct MainUI {
     spawn_panel: SpawnPanel,
     legend: Legend,
     network_viz: NetworkVisualization,
+    error_panel: ErrorPanel,
+    recording_panel: RecordingPanel,
+    journal_panel: JournalPanel,
+    console_panel: ConsolePanel,
+    dashboard_panel: DashboardPanel,
+    trace_panel: TracePanel,
+    flood_panel: FloodPanel,
+    selection_panel: SelectionPanel,
 }
 
 impl Default for MainUI {
@@ -25,28 +38,112 @@ impl MainUI {
             spawn_panel: SpawnPanel::new(),
             legend: Legend::new(),
             network_viz,
+            error_panel: ErrorPanel::new(),
+            recording_panel: RecordingPanel::new(),
+            journal_panel: JournalPanel::new(),
+            console_panel: ConsolePanel::new(),
+            dashboard_panel: DashboardPanel::new(),
+            trace_panel: TracePanel::new(),
+            flood_panel: FloodPanel::new(),
+            selection_panel: SelectionPanel::new(),
         }
     }
 
-    pub fn render(&mut self, state: &mut GUIState, ctx: &egui::Context) {
+    pub fn render(&mut self, core: &mut GuiCore, ctx: &egui::Context) {
+        HistoryControls::handle_shortcuts(&mut core.state, ctx);
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Simulation Controller");
+            ui.horizontal(|ui| {
+                ui.heading("Simulation Controller");
+                Self::render_ingestion_status(&core.state, ui);
+            });
 
             // Render legend
             self.legend.render(ui);
 
             ui.add_space(10.0);
 
-            // Show Animations
-            self.network_viz.show_animation(state, ui);
+            ui.horizontal(|ui| {
+                // Show Animations
+                self.network_viz.show_animation(&mut core.state, ui);
+
+                ui.toggle_value(&mut core.state.show_heatmap, "Heatmap");
+                ui.toggle_value(&mut core.state.show_trace, "Trace");
+                ui.toggle_value(&mut core.state.show_floods, "Floods");
+
+                self.network_viz.render_camera_controls(ui);
+                self.network_viz.render_layout_menu(&mut core.state, ui);
+
+                ui.toggle_value(&mut self.journal_panel.open, "Journal");
+                ui.toggle_value(&mut self.console_panel.open, "Console");
+                ui.toggle_value(&mut self.dashboard_panel.open, "Dashboard");
+
+                ui.separator();
+
+                // Render undo/redo controls
+                HistoryControls::render(&mut core.state, ui);
+
+                ui.separator();
+
+                // Render recording and replay controls
+                self.recording_panel.render(core, ui);
+            });
 
             ui.add_space(10.0);
 
+            let state = &mut core.state;
+
             // Render spawn controls
             self.spawn_panel.render(state, ui);
 
             // Render network visualization
             self.network_viz.render(state, ui, ctx);
         });
+
+        // Render the command console
+        self.console_panel.render(&mut core.state, ctx);
+
+        // Render the metrics dashboard
+        self.dashboard_panel.render(&core.state, ctx);
+
+        // Render the route trace of recent sessions
+        if core.state.show_trace {
+            self.trace_panel.render(&mut core.state, ctx);
+        }
+
+        // Render the recent network discoveries
+        if core.state.show_floods {
+            self.flood_panel.render(&mut core.state, ctx);
+        }
+
+        // Render the bulk actions on the selected nodes
+        self.selection_panel.render(&mut core.state, ctx);
+
+        // Render the command journal
+        self.journal_panel.render(core, ctx);
+
+        // Render errors raised while handling events and commands
+        self.error_panel.render(&mut core.state, ctx);
+    }
+
+    fn render_ingestion_status(state: &GUIState, ui: &mut egui::Ui) {
+        let ingestion = state.ingestion;
+        ui.label(format!(
+            "Event backlog: {} | processed: {} | coalesced: {}",
+            ingestion.backlog, ingestion.processed, ingestion.coalesced
+        ));
+
+        if state.disconnected {
+            ui.colored_label(
+                egui::Color32::LIGHT_RED,
+                "Simulation Controller disconnected",
+            );
+        }
+
+        let pending = state.pending.commands().len();
+        if pending > 0 {
+            ui.spinner();
+            ui.label(format!("{pending} commands waiting for confirmation"));
+        }
     }
 }
@@ -1,7 +1,22 @@
+pub mod camera;
+pub mod console;
+pub mod dashboard;
+pub mod errors;
+pub mod floods;
+pub mod heatmap;
+pub mod history;
+pub mod interaction;
+pub mod journal;
+pub mod layout;
 pub mod legend;
 pub mod main_ui;
 pub mod network;
 pub mod node;
+pub mod overlays;
+pub mod recording;
+pub mod selection;
 pub mod spawn;
+pub mod tooltip;
+pub mod trace;
 
 pub use main_ui::MainUI;
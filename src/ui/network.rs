@@ -1,20 +1,32 @@
-use eframe::egui::{self, Color32, Pos2, Rect, Sense, Stroke, Vec2};
+use eframe::egui::{self, Color32, Pos2, Sense, Stroke, Vec2};
 use wg_2024::{network::NodeId, packet::NodeType};
 
 use crate::{
     constants::{
-        CHAT_CLIENT_COLOR, COMMUNICATION_SERVER_COLOR, DRONE_COLOR, HEIGHT, MEDIA_CLIENT_COLOR,
-        MEDIA_CONTENT_SERVER_COLOR, NODE_RADIUS, TEXT_CONTENT_SERVER_COLOR, WIDTH,
+        CHAT_CLIENT_COLOR, COMMUNICATION_SERVER_COLOR, DRONE_COLOR, MEDIA_CLIENT_COLOR,
+        MEDIA_CONTENT_SERVER_COLOR, PACKET_MARKER_RADIUS, TEXT_CONTENT_SERVER_COLOR,
     },
     logic::{
+        layout,
         nodes::{types::ClientType, NodeGUI},
         state::GUIState,
     },
+    ui::camera::Camera,
 };
 use messages::high_level_messages::ServerType;
 
+/// Canvas the network is drawn on. The features drawn over it, such as the heatmap, floods,
+/// route trace, selection and tooltips, are implemented in their own `ui` modules
 #[derive(Debug)]
-pub struct NetworkVisualization;
+pub struct NetworkVisualization {
+    pub camera: Camera,
+    /// Node being dragged, `None` while panning
+    pub(super) dragged_node: Option<NodeId>,
+    /// Corners of the selection rectangle being dragged, in screen coordinates
+    pub(super) rubber_band: Option<(Pos2, Pos2)>,
+    /// File node positions are saved to and loaded from
+    pub(super) positions_path: String,
+}
 
 impl Default for NetworkVisualization {
     fn default() -> Self {
@@ -25,102 +37,88 @@ impl Default for NetworkVisualization {
 impl NetworkVisualization {
     #[must_use]
     pub fn new() -> Self {
-        Self
+        Self {
+            camera: Camera::new(),
+            dragged_node: None,
+            rubber_band: None,
+            positions_path: "positions.json".to_string(),
+        }
     }
 
     pub fn render(&mut self, state: &mut GUIState, ui: &mut egui::Ui, ctx: &egui::Context) {
-        // Allocate space for drawing
-        let (response, painter) =
-            ui.allocate_painter(Vec2::new(WIDTH, HEIGHT), Sense::click_and_drag());
+        // Fill the rest of the window, scaling the layout when the window is resized
+        let (response, painter) = ui.allocate_painter(
+            ui.available_size().max(Vec2::splat(100.0)),
+            Sense::click_and_drag(),
+        );
+        state.resize_canvas(response.rect.size());
 
-        // Offset the painter to account for header space
-        let painter = painter.with_clip_rect(Rect::from_min_max(
-            egui::pos2(0.0, 100.0),
-            egui::pos2(WIDTH, HEIGHT),
-        ));
+        // Move the camera, now that the canvas size is known
+        self.camera.apply_request(response.rect, state);
 
-        // Draw network connections
-        Self::draw_connections(&painter, state);
+        // Select, drag, pan and zoom
+        self.handle_interactions(ui, state, &response);
 
-        // Draw nodes and handle interactions
-        Self::draw_nodes_and_handle_interactions(ui, &painter, state, &response);
+        // Move the nodes towards a newly selected layout
+        layout::animate(state, ctx.input(|i| i.stable_dt));
 
-        // Update node colors based on type
-        self.update_node_colors(state);
+        let camera = &self.camera;
 
-        // render NodeDetails -> pop-up
-        self.render_nodes(state, ctx);
-    }
+        // Draw network connections
+        Self::draw_connections(&painter, state, camera);
 
-    fn draw_connections(painter: &egui::Painter, state: &GUIState) {
-        for (start_id, (neighbors, color)) in &state.edges {
-            if let Some(start_node) = state.nodes.get(start_id) {
-                for end_id in neighbors {
-                    if let Some(end_node) = state.nodes.get(end_id) {
-                        painter.line_segment(
-                            [
-                                egui::pos2(start_node.x, start_node.y),
-                                egui::pos2(end_node.x, end_node.y),
-                            ],
-                            Stroke::new(2.0, *color),
-                        );
-                    }
-                }
-            }
+        // Draw network discoveries spreading over the graph
+        if state.show_floods {
+            Self::draw_floods(&painter, state, camera);
         }
-    }
 
-    fn draw_nodes_and_handle_interactions(
-        ui: &mut egui::Ui,
-        painter: &egui::Painter,
-        state: &mut GUIState,
-        response: &egui::Response,
-    ) {
-        let mut nodes_to_update = Vec::new();
+        // Highlight the route of the traced session
+        if state.show_trace {
+            Self::draw_route_trace(&painter, state, camera);
+        }
 
-        // Collect nodes that need interaction handling
-        for (id, node) in &state.nodes {
-            nodes_to_update.push((*id, node.x, node.y, node.color));
+        // Draw packets travelling along the edges
+        if state.show_animation {
+            state.animations.advance(ctx.input(|i| i.stable_dt));
+            Self::draw_packets(&painter, state, camera);
         }
 
-        // Handle interactions and draw nodes
-        for (node_id, x, y, color) in nodes_to_update {
-            let screen_pos = egui::pos2(x, y);
+        // Draw nodes
+        Self::draw_nodes(&painter, state, camera);
 
-            // Create interaction area for the node
-            let node_rect = Rect::from_center_size(screen_pos, Vec2::splat(NODE_RADIUS * 2.0));
+        // Outline the selected nodes and the selection rectangle
+        self.draw_selection(&painter, state, camera);
 
-            let node_response = ui.allocate_rect(node_rect, Sense::click());
+        // Point out the drones spawned recently
+        Self::draw_spawn_highlights(&painter, state, camera);
 
-            // Handle node selection
-            if node_response.clicked() {
-                if let Some(node) = state.nodes.get_mut(&node_id) {
-                    node.selected = true;
-                }
-            }
+        // Draw spinners on nodes and links waiting for a command confirmation
+        Self::draw_pending_commands(ui, state, camera);
 
-            // Handle node dragging
-            if response.dragged() {
-                if let Some(hover_pos) = response.hover_pos() {
-                    if node_rect.contains(hover_pos) {
-                        if let Some(node) = state.nodes.get_mut(&node_id) {
-                            let delta = response.drag_delta();
-                            node.x += delta.x;
-                            node.y += delta.y;
-
-                            // Keep nodes within bounds
-                            node.x = node.x.clamp(NODE_RADIUS, WIDTH - NODE_RADIUS);
-                            node.y = node.y.clamp(100.0 + NODE_RADIUS, HEIGHT - NODE_RADIUS);
-                        }
-                    }
-                }
-            }
+        // Flag drones that do not drop packets at their configured PDR
+        Self::draw_pdr_warnings(&painter, state, camera);
+
+        // Describe the node or link under the pointer
+        self.show_hover_tooltip(&painter, state, &response);
+
+        // Update node colors based on type
+        self.update_node_colors(state);
+
+        // render NodeDetails -> pop-up
+        self.render_nodes(state, ctx);
+    }
+
+    fn draw_nodes(painter: &egui::Painter, state: &GUIState, camera: &Camera) {
+        let radius = camera.node_radius();
+
+        for (node_id, node) in &state.nodes {
+            let screen_pos = camera.to_screen(node.position());
 
             // Draw the node
-            painter.circle_filled(screen_pos, NODE_RADIUS, color);
+            painter.circle_filled(screen_pos, radius, node.color);
 
-            // Optional: Draw node ID as text
-            if NODE_RADIUS > 8.0 {
+            // Draw the node ID when there is room for it
+            if radius > 8.0 {
                 painter.text(
                     screen_pos,
                     egui::Align2::CENTER_CENTER,
@@ -132,13 +130,38 @@ impl NetworkVisualization {
         }
     }
 
+    fn draw_packets(painter: &egui::Painter, state: &GUIState, camera: &Camera) {
+        for marker in state.animations.markers() {
+            let (Some(src), Some(dest)) =
+                (state.nodes.get(&marker.src), state.nodes.get(&marker.dest))
+            else {
+                continue;
+            };
+            let position = camera
+                .to_screen(src.position())
+                .lerp(camera.to_screen(dest.position()), marker.position());
+
+            match marker.burst() {
+                Some(burst) => {
+                    // Expanding, fading ring where the packet was dropped
+                    let color = Color32::RED.gamma_multiply(1.0 - burst);
+                    painter.circle_stroke(
+                        position,
+                        PACKET_MARKER_RADIUS * (1.0 + 3.0 * burst),
+                        Stroke::new(2.0, color),
+                    );
+                }
+                None => {
+                    painter.circle_filled(position, PACKET_MARKER_RADIUS, marker.color);
+                }
+            }
+        }
+    }
+
     fn update_node_colors(&self, state: &mut GUIState) {
         // Update colors based on node type
         for node in state.nodes.values_mut() {
-            // Only update if not in special state (e.g., packet animation)
-            if !node.pending_reset {
-                node.color = self.get_node_color(node);
-            }
+            node.color = self.get_node_color(node);
         }
     }
 
@@ -217,43 +240,11 @@ impl NetworkVisualization {
         categories.media_servers
     }
 
-    /// Set node position programmatically
+    /// Set node position programmatically, in world coordinates
     pub fn set_node_position(&self, state: &mut GUIState, node_id: NodeId, x: f32, y: f32) {
         if let Some(node) = state.nodes.get_mut(&node_id) {
-            node.x = x.clamp(NODE_RADIUS, WIDTH - NODE_RADIUS);
-            node.y = y.clamp(100.0 + NODE_RADIUS, HEIGHT - NODE_RADIUS);
-        }
-    }
-
-    /// Auto-arrange nodes in a circle layout
-    #[allow(clippy::cast_precision_loss)]
-    pub fn auto_arrange_nodes(&self, state: &mut GUIState) {
-        let node_count = state.nodes.len();
-        if node_count == 0 {
-            return;
-        }
-
-        let center_x = WIDTH / 2.0;
-        let center_y = f32::midpoint(HEIGHT, 100.0);
-        let radius = (WIDTH.min(HEIGHT - 100.0) / 2.0) - NODE_RADIUS * 2.0;
-
-        for (i, (_, node)) in state.nodes.iter_mut().enumerate() {
-            let angle = (i as f32) * 2.0 * std::f32::consts::PI / (node_count as f32);
-            node.x = center_x + radius * angle.cos();
-            node.y = center_y + radius * angle.sin();
-        }
-    }
-
-    /// Get node at position (for hover detection)
-    #[must_use]
-    pub fn get_node_at_position(&self, state: &GUIState, pos: Pos2) -> Option<NodeId> {
-        for (id, node) in &state.nodes {
-            let node_pos = egui::pos2(node.x, node.y);
-            let distance = (pos - node_pos).length();
-            if distance <= NODE_RADIUS {
-                return Some(*id);
-            }
+            node.x = x;
+            node.y = y;
         }
-        None
     }
 }
@@ -1,29 +1,26 @@
 use colored::Colorize;
-use eframe::egui;
-use log::{error, info};
-use std::time::Duration;
+use eframe::egui::{self, Color32, Pos2, Stroke, Vec2};
+use log::info;
 use wg_2024::packet::NodeType;
 
 use crate::{
-    constants::DRONE_COLOR,
+    constants::{NODE_TRAFFIC_WINDOW_SECS, PDR_CHECK_MIN_SAMPLES, PDR_CHECK_WINDOWS},
     logic::{
         actions::{
             add_sender, ask_for_file_list, crash, get_file, get_list, logout, register,
             remove_sender, send_message, set_pdr,
         },
+        error::GuiError,
         nodes::{types::ClientType, NodeGUI},
+        pdr::PdrVerdict,
         state::GUIState,
+        traffic::PacketKind,
     },
     ui::network::NetworkVisualization,
 };
 
 impl NetworkVisualization {
     pub fn render_nodes(&mut self, state: &mut GUIState, ctx: &egui::Context) {
-        // Update node colors based on packet animation timing
-        if state.show_animation {
-            Self::update_node_animations(state);
-        }
-
         // Collect node IDs of selected nodes
         let mut selected_ids = Vec::new();
         for (id, instance) in &state.nodes {
@@ -32,16 +29,30 @@ impl NetworkVisualization {
             }
         }
 
-        // Now mutate the actual instances
+        // Render a copy of each instance so actions still see it in `state.nodes`
         for node_id in selected_ids {
-            if let Some(mut instance) = state.nodes.remove(&node_id) {
+            if let Some(mut instance) = state.nodes.get(&node_id).cloned() {
                 self.render_node_window(state, &mut instance, ctx);
 
-                state.nodes.insert(node_id, instance);
+                if let Some(node) = state.nodes.get_mut(&node_id) {
+                    Self::write_back_window(node, instance);
+                }
             }
         }
     }
 
+    /// Copy the fields edited by the node window back to the node, leaving the ones
+    /// changed by the actions untouched
+    fn write_back_window(node: &mut NodeGUI, window: NodeGUI) {
+        node.selected = window.selected;
+        node.remove_sender = window.remove_sender;
+        node.add_sender = window.add_sender;
+        node.drone_params.set_pdr = window.drone_params.set_pdr;
+        node.drone_params.pdr_value = window.drone_params.pdr_value;
+        node.chat_params = window.chat_params;
+        node.media_params = window.media_params;
+    }
+
     #[allow(clippy::explicit_iter_loop)]
     pub fn show_animation(&self, state: &mut GUIState, ui: &mut egui::Ui) {
         ui.horizontal_wrapped(|ui| {
@@ -57,26 +68,28 @@ impl NetworkVisualization {
                     let color = self.get_node_color(instance);
                     instance.color = color;
                 }
+                state.animations.clear();
             }
-        });
-    }
 
-    #[allow(clippy::explicit_iter_loop)]
-    fn update_node_animations(state: &mut GUIState) {
-        for (_, instance) in state.nodes.iter_mut() {
-            if let Some(start_time) = instance.last_packet_time {
-                if start_time.elapsed() > Duration::from_secs_f32(0.005) {
-                    if instance.pending_reset && instance.node_type == NodeType::Drone {
-                        instance.color = DRONE_COLOR;
-                    }
-                    instance.pending_reset = false;
-                }
+            if state.show_animation {
+                let animations = &mut state.animations;
+                ui.add(
+                    egui::Slider::new(&mut animations.speed, 0.1..=10.0)
+                        .logarithmic(true)
+                        .text("edges/s"),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut animations.max_markers)
+                        .range(0..=5000)
+                        .prefix("max packets: "),
+                );
+                ui.label(format!("{} in flight", animations.len()));
             }
-        }
+        });
     }
 
     fn render_node_window(
-        &self,
+        &mut self,
         state: &mut GUIState,
         instance: &mut NodeGUI,
         ctx: &egui::Context,
@@ -88,7 +101,8 @@ impl NetworkVisualization {
             .collapsible(true)
             .show(ctx, |ui| {
                 if !instance.drone_params.crashed {
-                    Self::render_node_info(ui, instance);
+                    Self::render_node_info(state, ui, instance);
+                    Self::render_traffic(state, ui, instance);
                     Self::render_action_buttons(state, ui, instance);
                     self.render_interactive_controls(state, ui, instance);
                 }
@@ -97,12 +111,82 @@ impl NetworkVisualization {
 
                 ui.add_space(20.0);
 
-                if ui.button("Close").clicked() {
-                    instance.selected = false;
-                }
+                ui.horizontal(|ui| {
+                    if ui.button("Focus").clicked() {
+                        self.camera.focus(instance.id);
+                    }
+                    if ui.button("Close").clicked() {
+                        instance.selected = false;
+                    }
+                });
             });
     }
 
+    fn render_traffic(state: &GUIState, ui: &mut egui::Ui, instance: &NodeGUI) {
+        let Some(traffic) = state.traffic.node(instance.id) else {
+            ui.weak("No traffic yet");
+            return;
+        };
+
+        egui::CollapsingHeader::new(format!(
+            "Traffic: {} sent, {} dropped",
+            traffic.total_sent(),
+            traffic.dropped()
+        ))
+        .id_salt(("traffic", instance.id))
+        .show(ui, |ui| {
+            egui::Grid::new(("traffic_grid", instance.id))
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Packet");
+                    ui.strong("Total");
+                    ui.strong(format!("Last {NODE_TRAFFIC_WINDOW_SECS}s"));
+                    ui.end_row();
+
+                    for kind in PacketKind::ALL {
+                        ui.label(format!("{kind:?}"));
+                        ui.label(traffic.sent(kind).to_string());
+                        ui.label(format!("{:.2}/s", traffic.rate(kind)));
+                        ui.end_row();
+                    }
+
+                    ui.label("Dropped");
+                    ui.label(traffic.dropped().to_string());
+                    ui.label(format!("{:.2}/s", traffic.drop_rate()));
+                    ui.end_row();
+                });
+
+            Self::render_sparkline(ui, &traffic.sparkline());
+        });
+    }
+
+    /// Packets per second over the traffic window, newest on the right
+    fn render_sparkline(ui: &mut egui::Ui, values: &[u32]) {
+        let (rect, response) = ui.allocate_exact_size(Vec2::new(180.0, 30.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        let peak = values.iter().copied().max().unwrap_or(0);
+        let max = peak.max(1);
+        #[allow(clippy::cast_precision_loss)]
+        let points: Vec<Pos2> = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let x = rect.left() + rect.width() * i as f32 / (values.len().max(2) - 1) as f32;
+                let y = rect.bottom() - rect.height() * *value as f32 / max as f32;
+                Pos2::new(x, y)
+            })
+            .collect();
+        painter.add(egui::Shape::line(
+            points,
+            Stroke::new(1.5, Color32::LIGHT_BLUE),
+        ));
+
+        response.on_hover_text(format!("Peak: {peak} packets/s"));
+    }
+
     fn get_window_title(instance: &NodeGUI) -> String {
         match instance.node_type {
             NodeType::Server => {
@@ -127,17 +211,69 @@ impl NetworkVisualization {
         }
     }
 
-    fn render_node_info(ui: &mut egui::Ui, instance: &NodeGUI) {
+    fn render_node_info(state: &GUIState, ui: &mut egui::Ui, instance: &NodeGUI) {
         ui.label(format!("Id: {}", instance.id));
-        ui.label(format!("Neighbors: {:?}", instance.neighbor));
+        ui.label(format!(
+            "Neighbors: {:?}",
+            state.graph.neighbors(instance.id)
+        ));
 
         if instance.node_type == NodeType::Drone {
-            ui.label(format!("PDR: {}", instance.pdr));
+            ui.horizontal(|ui| {
+                ui.label(format!("PDR: {}", instance.pdr));
+                if state.pending.pdr(instance.id).is_some() {
+                    ui.spinner();
+                    ui.weak("sent");
+                } else if instance.drone_params.unconfirmed_pdr.is_some() {
+                    ui.weak("unconfirmed")
+                        .on_hover_text("The Simulation Controller does not acknowledge a new PDR");
+                }
+            });
+            Self::render_pdr_check(state, ui, instance);
         }
 
         ui.add_space(10.0);
     }
 
+    fn render_pdr_check(state: &GUIState, ui: &mut egui::Ui, instance: &NodeGUI) {
+        let Some(observation) = state
+            .pdr_checks
+            .get(instance.id)
+            .filter(|observation| observation.samples() > 0)
+        else {
+            ui.weak("Observed PDR: no fragments yet");
+            return;
+        };
+        let (Some(observed), Some((low, high))) =
+            (observation.observed(), observation.confidence_interval())
+        else {
+            return;
+        };
+
+        let text = format!(
+            "Observed PDR: {observed:.3} [{low:.3}, {high:.3}] over {} fragments",
+            observation.samples()
+        );
+        match observation.verdict() {
+            PdrVerdict::NotEnoughData => {
+                ui.label(text).on_hover_text(format!(
+                    "At least {} fragments are needed",
+                    PDR_CHECK_MIN_SAMPLES * u64::from(PDR_CHECK_WINDOWS)
+                ));
+            }
+            PdrVerdict::Consistent => {
+                ui.colored_label(Color32::LIGHT_GREEN, text)
+                    .on_hover_text("The last PDR sent is within the 95% confidence interval");
+            }
+            PdrVerdict::Inconsistent => {
+                ui.colored_label(Color32::LIGHT_RED, text).on_hover_text(
+                    "The last PDR sent has been outside the 95% confidence interval over \
+                     several windows: the drone does not drop packets as configured",
+                );
+            }
+        }
+    }
+
     fn render_action_buttons(state: &mut GUIState, ui: &mut egui::Ui, instance: &mut NodeGUI) {
         ui.horizontal_wrapped(|ui| {
             // Common buttons for all node types
@@ -152,7 +288,8 @@ impl NetworkVisualization {
             // Drone-specific buttons
             if instance.node_type == NodeType::Drone {
                 if ui.button("Crash").clicked() {
-                    crash(state, instance.id);
+                    let result = crash(state, instance.id);
+                    state.report(result);
                 }
 
                 if ui.button("SetPacketDropRate").clicked() {
@@ -174,7 +311,8 @@ impl NetworkVisualization {
             }
 
             if ui.button("GetClientList").clicked() {
-                get_list(state, instance.id);
+                let result = get_list(state, instance.id);
+                state.report(result);
             }
 
             if ui.button("RegisterTo").clicked() {
@@ -183,7 +321,8 @@ impl NetworkVisualization {
 
             if ui.button("LogOut").clicked() {
                 if let Some(server) = instance.chat_params.register_value {
-                    logout(state, instance.id, server);
+                    let result = logout(state, instance.id, server);
+                    state.report(result);
                 }
             }
         } else if let Some(ClientType::Media) = instance.client_type {
@@ -225,17 +364,23 @@ impl NetworkVisualization {
         egui::ComboBox::from_label("Select Sender to remove:")
             .selected_text("None")
             .show_ui(ui, |ui| {
-                let mut options: Vec<String> =
-                    instance.neighbor.iter().map(ToString::to_string).collect();
-                options.sort_by_key(|s| s.parse::<i32>().unwrap_or(0));
+                let options: Vec<String> = state
+                    .graph
+                    .neighbors(instance.id)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
 
                 for option in options {
                     if ui.selectable_label(false, &option).clicked() {
                         if let Ok(digit) = option.parse::<u8>() {
                             instance.remove_sender = false;
-                            remove_sender(state, instance.id, digit);
+                            let result = remove_sender(state, instance.id, digit);
+                            state.report(result);
                         } else {
-                            error!("[ {} ] Invalid neighbor ID: {}", "GUI".red(), option);
+                            state.record_error(GuiError::InvalidInput(format!(
+                                "Invalid neighbor ID: {option}"
+                            )));
                         }
                     }
                 }
@@ -247,10 +392,10 @@ impl NetworkVisualization {
             .selected_text("None")
             .show_ui(ui, |ui| {
                 let mut options: Vec<String> = state
-                    .edges
-                    .keys()
-                    .filter(|&id| !instance.neighbor.contains(id) && *id != instance.id)
-                    .map(ToString::to_string)
+                    .graph
+                    .nodes()
+                    .filter(|&id| id != instance.id && !state.graph.contains_edge(instance.id, id))
+                    .map(|id| id.to_string())
                     .collect();
                 options.sort_by_key(|s| s.parse::<i32>().unwrap_or(0));
 
@@ -258,9 +403,12 @@ impl NetworkVisualization {
                     if ui.selectable_label(false, &option).clicked() {
                         if let Ok(digit) = option.parse::<u8>() {
                             instance.add_sender = false;
-                            add_sender(state, instance.id, digit);
+                            let result = add_sender(state, instance.id, digit);
+                            state.report(result);
                         } else {
-                            error!("[ {} ] Invalid neighbor ID: {}", "GUI".red(), option);
+                            state.record_error(GuiError::InvalidInput(format!(
+                                "Invalid neighbor ID: {option}"
+                            )));
                         }
                     }
                 }
@@ -284,18 +432,15 @@ impl NetworkVisualization {
                     if let Some(pdr_value) = &instance.drone_params.pdr_value {
                         match pdr_value.parse::<f32>() {
                             Ok(pdr) => {
-                                if (0.0..=1.0).contains(&pdr) {
+                                let result = set_pdr(state, instance.id, pdr);
+                                if result.is_ok() {
                                     instance.drone_params.set_pdr = false;
-                                    set_pdr(state, instance, pdr);
-                                } else {
-                                    error!(
-                                        "[ {} ] Invalid PDR input: {}",
-                                        "GUI".red(),
-                                        "The PDR value must be between 0.0 and 1.0"
-                                    );
                                 }
+                                state.report(result);
                             }
-                            Err(e) => error!("[ {} ] Invalid PDR input: {}", "GUI".red(), e),
+                            Err(e) => state.record_error(GuiError::InvalidInput(format!(
+                                "Invalid PDR input: {e}"
+                            ))),
                         }
                     }
                 }
@@ -377,12 +522,18 @@ impl NetworkVisualization {
                         instance.chat_params.send_message_client_value = None;
                         instance.chat_params.send_message_msg_value = None;
 
-                        send_message(state, instance.id, client_id, &message);
+                        let result = send_message(state, instance.id, client_id, &message);
+
+                        state.report(result);
                     } else {
-                        error!("[ {} ] Invalid client ID format", "GUI".red());
+                        state.record_error(GuiError::InvalidInput(
+                            "Invalid client ID format".to_string(),
+                        ));
                     }
                 } else {
-                    error!("[ {} ] Missing client or message", "GUI".red());
+                    state.record_error(GuiError::InvalidInput(
+                        "Missing client or message".to_string(),
+                    ));
                 }
             }
         });
@@ -407,9 +558,13 @@ impl NetworkVisualization {
                             instance.chat_params.register_value = Some(digit);
                             instance.chat_params.register_to = false;
 
-                            register(state, instance.id, digit);
+                            let result = register(state, instance.id, digit);
+
+                            state.report(result);
                         } else {
-                            error!("[ {} ] Invalid Server ID: {}", "GUI".red(), option);
+                            state.record_error(GuiError::InvalidInput(format!(
+                                "Invalid Server ID: {option}"
+                            )));
                         }
                     }
                 }
@@ -456,9 +611,13 @@ impl NetworkVisualization {
                             instance.media_params.ask_for_file_list = false;
                             instance.media_params.get_file = true;
 
-                            ask_for_file_list(state, instance.id, digit);
+                            let result = ask_for_file_list(state, instance.id, digit);
+
+                            state.report(result);
                         } else {
-                            error!("[ {} ] Invalid Server ID: {}", "GUI".red(), option);
+                            state.record_error(GuiError::InvalidInput(format!(
+                                "Invalid Server ID: {option}"
+                            )));
                         }
                     }
                 }
@@ -478,7 +637,9 @@ impl NetworkVisualization {
                             if ui.selectable_label(false, &option).clicked() {
                                 instance.media_params.get_file = false;
 
-                                get_file(state, instance.id, server_id, &option);
+                                let result = get_file(state, instance.id, server_id, &option);
+
+                                state.report(result);
                             }
                         }
                     }
@@ -0,0 +1,75 @@
+use eframe::egui::{self, Color32, Rect, Stroke, Vec2};
+
+use crate::{
+    constants::SPAWN_HIGHLIGHT_SECS,
+    logic::state::GUIState,
+    ui::{camera::Camera, network::NetworkVisualization},
+};
+
+impl NetworkVisualization {
+    pub(super) fn draw_spawn_highlights(
+        painter: &egui::Painter,
+        state: &mut GUIState,
+        camera: &Camera,
+    ) {
+        state
+            .spawned
+            .retain(|_, spawned_at| spawned_at.elapsed().as_secs_f32() < SPAWN_HIGHLIGHT_SECS);
+
+        let radius = camera.node_radius();
+        for (id, spawned_at) in &state.spawned {
+            let Some(node) = state.nodes.get(id) else {
+                continue;
+            };
+            let age = spawned_at.elapsed().as_secs_f32() / SPAWN_HIGHLIGHT_SECS;
+            // A ring pulsing twice a second, fading out
+            let pulse = (spawned_at.elapsed().as_secs_f32() * 2.0).fract();
+            painter.circle_stroke(
+                camera.to_screen(node.position()),
+                radius * (1.5 + pulse),
+                Stroke::new(3.0, Color32::GOLD.gamma_multiply(1.0 - age)),
+            );
+        }
+    }
+
+    pub(super) fn draw_pdr_warnings(painter: &egui::Painter, state: &GUIState, camera: &Camera) {
+        let radius = camera.node_radius();
+
+        for drone in state.pdr_checks.inconsistent() {
+            if let Some(node) = state.nodes.get(&drone) {
+                let center = camera.to_screen(node.position());
+                painter.circle_stroke(center, radius + 3.0, Stroke::new(2.0, Color32::RED));
+                painter.text(
+                    center + Vec2::new(radius, -radius),
+                    egui::Align2::LEFT_BOTTOM,
+                    "!",
+                    egui::FontId::proportional(14.0),
+                    Color32::RED,
+                );
+            }
+        }
+    }
+
+    pub(super) fn draw_pending_commands(ui: &egui::Ui, state: &GUIState, camera: &Camera) {
+        let spinner = egui::Spinner::new().color(Color32::WHITE);
+
+        for node_id in state.pending.nodes() {
+            if let Some(node) = state.nodes.get(&node_id) {
+                let rect = Rect::from_center_size(
+                    camera.to_screen(node.position()),
+                    Vec2::splat(camera.node_radius() * 2.0 + 8.0),
+                );
+                spinner.paint_at(ui, rect);
+            }
+        }
+
+        for (a, b) in state.pending.edges() {
+            if let (Some(start), Some(end)) = (state.nodes.get(&a), state.nodes.get(&b)) {
+                let middle = camera
+                    .to_screen(start.position())
+                    .lerp(camera.to_screen(end.position()), 0.5);
+                spinner.paint_at(ui, Rect::from_center_size(middle, Vec2::splat(16.0)));
+            }
+        }
+    }
+}
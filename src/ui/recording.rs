@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use eframe::egui;
+
+use crate::logic::GuiCore;
+
+/// Controls to record incoming events, or to drive a replay
+pub struct RecordingPanel {
+    path: String,
+}
+
+impl Default for RecordingPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordingPanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            path: "recording.jsonl".to_string(),
+        }
+    }
+
+    pub fn render(&mut self, core: &mut GuiCore, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            if core.replay_state().is_some() {
+                Self::render_replay_controls(core, ui);
+            } else {
+                self.render_recording_controls(core, ui);
+            }
+        });
+    }
+
+    fn render_recording_controls(&mut self, core: &mut GuiCore, ui: &mut egui::Ui) {
+        if let Some(recorder) = core.recorder() {
+            ui.label(format!(
+                "Recording {} events to {}",
+                recorder.recorded(),
+                recorder.path().display()
+            ));
+
+            if ui.button("Stop Recording").clicked() {
+                let result = core.stop_recording();
+                core.state.report(result);
+            }
+        } else {
+            ui.label("Record to:");
+            ui.text_edit_singleline(&mut self.path);
+
+            if ui.button("Start Recording").clicked() {
+                let result = core.start_recording(&self.path);
+                core.state.report(result);
+            }
+        }
+    }
+
+    fn render_replay_controls(core: &mut GuiCore, ui: &mut egui::Ui) {
+        let Some(replay) = core.replay_mut() else {
+            return;
+        };
+
+        let label = if replay.playing { "Pause" } else { "Play" };
+        if ui.button(label).clicked() {
+            replay.playing = !replay.playing;
+        }
+
+        if ui.button("Step").clicked() {
+            replay.step();
+        }
+
+        ui.add(
+            egui::Slider::new(&mut replay.speed, 0.1..=20.0)
+                .logarithmic(true)
+                .text("speed"),
+        );
+
+        ui.label(format!(
+            "Replay: event {}/{} | {:.1}s / {:.1}s",
+            replay.position(),
+            replay.len(),
+            replay.clock_ms() / 1000.0,
+            Duration::from_millis(replay.duration_ms()).as_secs_f64()
+        ));
+    }
+}
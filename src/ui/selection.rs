@@ -0,0 +1,239 @@
+use eframe::egui::{self, Rect, Stroke, Vec2};
+use wg_2024::{network::NodeId, packet::NodeType};
+
+use crate::{
+    constants::SELECTION_COLOR,
+    logic::{
+        actions::{crash_selected, remove_links_between_groups, selected_drones, set_pdr_selected},
+        state::GUIState,
+    },
+    ui::{camera::Camera, network::NetworkVisualization},
+};
+
+/// Panel with the bulk actions on the nodes selected on the canvas, and an inspector
+/// comparing them
+pub struct SelectionPanel {
+    /// PDR set on every selected drone
+    pdr: f32,
+    inspector_open: bool,
+}
+
+impl Default for SelectionPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SelectionPanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            pdr: 0.0,
+            inspector_open: false,
+        }
+    }
+
+    pub fn render(&mut self, state: &mut GUIState, ctx: &egui::Context) {
+        if state.selection.is_empty() && !state.selection.has_groups() {
+            return;
+        }
+
+        egui::Window::new(format!("Selection ({})", state.selection.len()))
+            .resizable(true)
+            .collapsible(true)
+            .default_width(350.0)
+            .show(ctx, |ui| {
+                ui.label(format!("Nodes: {}", Self::ids_text(state.selection.iter())));
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Clear")
+                        .on_hover_text("Deselect every node and forget the groups")
+                        .clicked()
+                    {
+                        state.selection.reset();
+                    }
+                    ui.toggle_value(&mut self.inspector_open, "Inspector");
+                });
+
+                ui.separator();
+
+                self.render_drone_actions(state, ui);
+
+                ui.separator();
+
+                Self::render_groups(state, ui);
+            });
+
+        if self.inspector_open {
+            Self::render_inspector(state, &mut self.inspector_open, ctx);
+        }
+    }
+
+    fn render_drone_actions(&mut self, state: &mut GUIState, ui: &mut egui::Ui) {
+        let drones = selected_drones(state).len();
+        ui.label(format!("{drones} drones selected"));
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(drones > 0, egui::Button::new("Crash drones"))
+                .clicked()
+            {
+                let result = crash_selected(state);
+                state.report(result);
+            }
+
+            ui.add(
+                egui::DragValue::new(&mut self.pdr)
+                    .range(0.0..=1.0)
+                    .speed(0.01)
+                    .prefix("PDR: "),
+            );
+            if ui
+                .add_enabled(drones > 0, egui::Button::new("Set PDR"))
+                .clicked()
+            {
+                let result = set_pdr_selected(state, self.pdr);
+                state.report(result);
+            }
+        });
+    }
+
+    fn render_groups(state: &mut GUIState, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Set as group A").clicked() {
+                state.selection.group_a = state.selection.iter().collect();
+            }
+            ui.label(Self::ids_text(state.selection.group_a.iter().copied()));
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Set as group B").clicked() {
+                state.selection.group_b = state.selection.iter().collect();
+            }
+            ui.label(Self::ids_text(state.selection.group_b.iter().copied()));
+        });
+
+        let links = state.selection.links_between_groups(&state.graph).len();
+        if ui
+            .add_enabled(
+                links > 0,
+                egui::Button::new(format!("Remove {links} links between A and B")),
+            )
+            .clicked()
+        {
+            let result = remove_links_between_groups(state);
+            state.report(result);
+        }
+    }
+
+    /// One row per selected node, with their totals
+    fn render_inspector(state: &GUIState, open: &mut bool, ctx: &egui::Context) {
+        egui::Window::new("Selection Inspector")
+            .open(open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                let (mut total_sent, mut total_dropped) = (0, 0);
+
+                egui::Grid::new("selection_inspector")
+                    .num_columns(6)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["Node", "Role", "Degree", "PDR", "Sent", "Dropped"] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+
+                        for id in state.selection.iter() {
+                            let Some(node) = state.nodes.get(&id) else {
+                                continue;
+                            };
+                            let (sent, dropped) =
+                                state.traffic.node(id).map_or((0, 0), |traffic| {
+                                    (traffic.total_sent(), traffic.dropped())
+                                });
+                            total_sent += sent;
+                            total_dropped += dropped;
+
+                            ui.label(id.to_string());
+                            ui.label(node.role());
+                            ui.label(state.graph.neighbors(id).len().to_string());
+                            if node.node_type == NodeType::Drone {
+                                ui.label(format!("{:.2}", node.pdr));
+                            } else {
+                                ui.label("-");
+                            }
+                            ui.label(sent.to_string());
+                            ui.label(dropped.to_string());
+                            ui.end_row();
+                        }
+
+                        ui.strong("Total");
+                        ui.label("");
+                        ui.label("");
+                        ui.label("");
+                        ui.strong(total_sent.to_string());
+                        ui.strong(total_dropped.to_string());
+                        ui.end_row();
+                    });
+            });
+    }
+
+    fn ids_text(ids: impl Iterator<Item = NodeId>) -> String {
+        let ids: Vec<String> = ids.map(|id| id.to_string()).collect();
+        if ids.is_empty() {
+            "none".to_string()
+        } else {
+            ids.join(", ")
+        }
+    }
+}
+
+impl NetworkVisualization {
+    /// Outline the selected nodes, mark the groups whose links can be cut, and draw the
+    /// selection rectangle being dragged
+    pub(super) fn draw_selection(
+        &self,
+        painter: &egui::Painter,
+        state: &GUIState,
+        camera: &Camera,
+    ) {
+        let radius = camera.node_radius();
+
+        for id in state.selection.iter() {
+            if let Some(node) = state.nodes.get(&id) {
+                painter.circle_stroke(
+                    camera.to_screen(node.position()),
+                    radius + 5.0,
+                    Stroke::new(2.5, SELECTION_COLOR),
+                );
+            }
+        }
+
+        for (group, label) in [
+            (&state.selection.group_a, "A"),
+            (&state.selection.group_b, "B"),
+        ] {
+            for id in group {
+                if let Some(node) = state.nodes.get(id) {
+                    painter.text(
+                        camera.to_screen(node.position()) + Vec2::new(-radius, radius),
+                        egui::Align2::RIGHT_TOP,
+                        label,
+                        egui::FontId::proportional(13.0),
+                        SELECTION_COLOR,
+                    );
+                }
+            }
+        }
+
+        if let Some((start, end)) = self.rubber_band {
+            let rect = Rect::from_two_pos(start, end);
+            painter.rect_filled(rect, 0.0, SELECTION_COLOR.gamma_multiply(0.1));
+            painter.rect_stroke(
+                rect,
+                0.0,
+                Stroke::new(1.0, SELECTION_COLOR),
+                egui::StrokeKind::Inside,
+            );
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use colored::Colorize;
 use eframe::egui;
-use log::{error, info};
+use log::info;
 
-use crate::logic::{actions::spawn, state::GUIState};
+use crate::logic::{actions::spawn, error::GuiError, state::GUIState};
 
 pub struct SpawnPanel;
 
@@ -97,14 +97,17 @@ impl SpawnPanel {
             if let Ok(id) = id_str.parse::<u8>() {
                 if let Ok(pdr) = pdr_str.parse::<f32>() {
                     let neighbors = state.spawn.neighbors.clone();
-                    spawn(state, id, &neighbors, pdr);
-                    state.reset_spawn_state();
-                    info!("[ {} ] Spawning new Drone: {}", "GUI".green(), id);
+                    let result = spawn(state, id, &neighbors, pdr);
+                    if result.is_ok() {
+                        state.reset_spawn_state();
+                        info!("[ {} ] Spawning new Drone: {}", "GUI".green(), id);
+                    }
+                    state.report(result);
                 } else {
-                    error!("[ {} ] Invalid PDR value", "GUI".red());
+                    state.record_error(GuiError::InvalidInput("Invalid PDR value".to_string()));
                 }
             } else {
-                error!("[ {} ] Invalid ID value", "GUI".red());
+                state.record_error(GuiError::InvalidInput("Invalid ID value".to_string()));
             }
         }
     }
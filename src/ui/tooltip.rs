@@ -0,0 +1,118 @@
+use eframe::egui::{self, Color32, Stroke};
+use wg_2024::{network::NodeId, packet::NodeType};
+
+use crate::{
+    logic::{nodes::NodeGUI, pdr::PdrObservation, state::GUIState, traffic::PacketKind},
+    ui::network::NetworkVisualization,
+};
+
+impl NetworkVisualization {
+    /// Highlight the node or link under the pointer and show a tooltip describing it
+    pub(super) fn show_hover_tooltip(
+        &self,
+        painter: &egui::Painter,
+        state: &GUIState,
+        response: &egui::Response,
+    ) {
+        if response.dragged() {
+            return;
+        }
+        let Some(pos) = response.hover_pos() else {
+            return;
+        };
+        let highlight = Stroke::new(2.0, Color32::WHITE);
+
+        if let Some(id) = self.get_node_at_position(state, self.camera.to_world(pos)) {
+            if let Some(node) = state.nodes.get(&id) {
+                painter.circle_stroke(
+                    self.camera.to_screen(node.position()),
+                    self.camera.node_radius() + 2.0,
+                    highlight,
+                );
+            }
+            response
+                .clone()
+                .on_hover_ui_at_pointer(|ui| Self::node_tooltip(state, id, ui));
+        } else if let Some((a, b)) = self.get_edge_at_position(state, pos) {
+            if let (Some(start), Some(end)) = (state.nodes.get(&a), state.nodes.get(&b)) {
+                painter.line_segment(
+                    [
+                        self.camera.to_screen(start.position()),
+                        self.camera.to_screen(end.position()),
+                    ],
+                    highlight,
+                );
+            }
+            response
+                .clone()
+                .on_hover_ui_at_pointer(|ui| Self::edge_tooltip(state, a, b, ui));
+        }
+    }
+
+    fn node_tooltip(state: &GUIState, id: NodeId, ui: &mut egui::Ui) {
+        let Some(node) = state.nodes.get(&id) else {
+            return;
+        };
+        ui.strong(format!("{} {id}", node.role()));
+
+        if node.node_type == NodeType::Drone {
+            match state.pdr_checks.get(id).and_then(PdrObservation::observed) {
+                Some(observed) => {
+                    ui.label(format!("PDR: {:.2} (observed {observed:.2})", node.pdr));
+                }
+                None => {
+                    ui.label(format!("PDR: {:.2}", node.pdr));
+                }
+            }
+        }
+
+        let mut neighbors = state.graph.neighbors(id);
+        neighbors.sort_unstable();
+        ui.label(format!("Degree: {}", neighbors.len()));
+        ui.label(format!(
+            "Neighbors: {}",
+            neighbors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+
+        let Some(traffic) = state.traffic.node(id) else {
+            ui.weak("No traffic yet");
+            return;
+        };
+        ui.separator();
+        for kind in PacketKind::ALL {
+            if traffic.sent(kind) > 0 {
+                ui.label(format!(
+                    "{kind:?}: {} sent ({:.2}/s)",
+                    traffic.sent(kind),
+                    traffic.rate(kind)
+                ));
+            }
+        }
+        ui.label(format!(
+            "Dropped: {} ({:.2}/s)",
+            traffic.dropped(),
+            traffic.drop_rate()
+        ));
+    }
+
+    fn edge_tooltip(state: &GUIState, a: NodeId, b: NodeId, ui: &mut egui::Ui) {
+        let role = |id: NodeId| state.nodes.get(&id).map_or("Node", NodeGUI::role);
+        ui.strong(format!("{} {a} ↔ {} {b}", role(a), role(b)));
+        ui.label(format!(
+            "{a} → {b}: {} packets",
+            state.traffic.link_sent(a, b)
+        ));
+        ui.label(format!(
+            "{b} → {a}: {} packets",
+            state.traffic.link_sent(b, a)
+        ));
+        ui.label(format!(
+            "Recent rate: {:.2} packets/s",
+            state.traffic.edge_rate(a, b)
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+use eframe::egui::{self, Color32, Stroke, Vec2};
+use wg_2024::network::NodeId;
+
+use crate::{
+    logic::{animation::kind_color, routes::RouteTrace, state::GUIState},
+    ui::{camera::Camera, network::NetworkVisualization},
+};
+
+/// Number of sessions listed in the trace panel
+const LISTED_TRACES: usize = 50;
+
+/// Panel listing the routes of recent sessions, the selected one is traced on the canvas
+pub struct TracePanel;
+
+impl Default for TracePanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TracePanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&mut self, state: &mut GUIState, ctx: &egui::Context) {
+        let mut open = state.show_trace;
+
+        egui::Window::new("Route Trace")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} sessions traced", state.routes.len()));
+                    if ui.button("Clear").clicked() {
+                        state.routes.clear();
+                    }
+                });
+                ui.weak("Select a session, or click a packet on the canvas");
+
+                ui.separator();
+
+                Self::render_sessions(state, ui);
+
+                ui.separator();
+
+                match state.routes.selected() {
+                    Some(trace) => Self::render_details(trace, ui),
+                    None => {
+                        ui.label("No session selected");
+                    }
+                }
+            });
+
+        state.show_trace = open;
+    }
+
+    fn render_sessions(state: &mut GUIState, ui: &mut egui::Ui) {
+        let mut selected = state.routes.selected;
+
+        egui::ScrollArea::vertical()
+            .max_height(250.0)
+            .show(ui, |ui| {
+                for (key, trace) in state.routes.recent().into_iter().take(LISTED_TRACES) {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(kind_color(trace.kind), "●");
+                        let text = format!(
+                            "Session {} {:?}: {} → {}",
+                            key.session_id,
+                            trace.kind,
+                            key.source,
+                            trace
+                                .destination()
+                                .map_or_else(|| "?".to_string(), |id| id.to_string())
+                        );
+                        if ui.selectable_label(selected == Some(key), text).clicked() {
+                            selected = (selected != Some(key)).then_some(key);
+                        }
+                        Self::render_status(trace, ui);
+                    });
+                }
+            });
+
+        state.routes.selected = selected;
+    }
+
+    fn render_status(trace: &RouteTrace, ui: &mut egui::Ui) {
+        if let Some(dropper) = trace.dropped_at {
+            ui.colored_label(Color32::LIGHT_RED, format!("dropped at {dropper}"));
+        } else if trace.is_delivered() {
+            ui.colored_label(Color32::LIGHT_GREEN, "delivered");
+        } else {
+            ui.colored_label(Color32::LIGHT_BLUE, "in flight");
+        }
+        if let Some(node) = trace.diverged_at {
+            ui.colored_label(Color32::ORANGE, format!("diverged at {node}"));
+        }
+    }
+
+    fn render_details(trace: &RouteTrace, ui: &mut egui::Ui) {
+        ui.label(format!(
+            "Planned route: {}",
+            Self::route_text(&trace.planned)
+        ));
+        ui.label(format!(
+            "Reached hop {} of {}",
+            trace.reached,
+            trace.planned.len().saturating_sub(1)
+        ));
+
+        let off_route: Vec<String> = trace
+            .travelled
+            .iter()
+            .filter(|(src, dest)| !trace.is_planned(*src, *dest))
+            .map(|(src, dest)| format!("{src} → {dest}"))
+            .collect();
+        if !off_route.is_empty() {
+            ui.colored_label(
+                Color32::ORANGE,
+                format!("Sent off the route: {}", off_route.join(", ")),
+            );
+        }
+
+        ui.label(format!(
+            "{} transmissions, last {:.1}s ago",
+            trace.transmissions,
+            trace.last_seen.elapsed().as_secs_f32()
+        ));
+        ui.horizontal(|ui| Self::render_status(trace, ui));
+    }
+
+    fn route_text(hops: &[NodeId]) -> String {
+        hops.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+}
+
+impl NetworkVisualization {
+    /// Draw the planned route of the selected session, the hops it reached, the links it
+    /// was sent over off the route and the node that dropped it
+    pub(super) fn draw_route_trace(painter: &egui::Painter, state: &GUIState, camera: &Camera) {
+        let Some(trace) = state.routes.selected() else {
+            return;
+        };
+        let screen = |id: &NodeId| {
+            state
+                .nodes
+                .get(id)
+                .map(|node| camera.to_screen(node.position()))
+        };
+        let radius = camera.node_radius();
+
+        // The planned route, solid up to the furthest hop reached
+        for (index, hop) in trace.planned.windows(2).enumerate() {
+            if let (Some(start), Some(end)) = (screen(&hop[0]), screen(&hop[1])) {
+                let color = if index < trace.reached {
+                    Color32::LIGHT_BLUE
+                } else {
+                    Color32::LIGHT_BLUE.gamma_multiply(0.35)
+                };
+                painter.line_segment([start, end], Stroke::new(6.0, color));
+            }
+        }
+
+        // Links used off the planned route
+        for (src, dest) in &trace.travelled {
+            if trace.is_planned(*src, *dest) {
+                continue;
+            }
+            if let (Some(start), Some(end)) = (screen(src), screen(dest)) {
+                painter.add(egui::Shape::dashed_line(
+                    &[start, end],
+                    Stroke::new(3.0, Color32::ORANGE),
+                    8.0,
+                    5.0,
+                ));
+            }
+        }
+
+        // Hop numbers, with a ring on the furthest hop reached
+        for (index, hop) in trace.planned.iter().enumerate() {
+            let Some(center) = screen(hop) else {
+                continue;
+            };
+            painter.text(
+                center + Vec2::new(-radius, -radius),
+                egui::Align2::RIGHT_BOTTOM,
+                index.to_string(),
+                egui::FontId::proportional(12.0),
+                Color32::LIGHT_BLUE,
+            );
+            if index == trace.reached {
+                painter.circle_stroke(center, radius + 4.0, Stroke::new(2.5, Color32::LIGHT_BLUE));
+            }
+        }
+
+        if let Some(center) = trace.diverged_at.as_ref().and_then(screen) {
+            painter.circle_stroke(center, radius + 7.0, Stroke::new(2.0, Color32::ORANGE));
+        }
+
+        // A cross over the node that dropped the session
+        if let Some(center) = trace.dropped_at.as_ref().and_then(screen) {
+            let stroke = Stroke::new(3.0, Color32::RED);
+            let arm = radius + 2.0;
+            painter.line_segment(
+                [center + Vec2::new(-arm, -arm), center + Vec2::new(arm, arm)],
+                stroke,
+            );
+            painter.line_segment(
+                [center + Vec2::new(-arm, arm), center + Vec2::new(arm, -arm)],
+                stroke,
+            );
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Drive the headless core through its command and event channels, playing the part of the
+//! Simulation Controller
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+use gui::{
+    constants::{PDR_CHECK_MIN_SAMPLES, PDR_CHECK_WINDOWS},
+    logic::{error::GuiError, journal::CommandOutcome, pdr::PdrVerdict, GuiCore},
+    mock::{MockController, MockSettings},
+};
+use messages::gui_commands::{GUICommands, GUIEvents};
+use wg_2024::{
+    config::Config,
+    network::SourceRoutingHeader,
+    packet::{Fragment, Packet, PacketType, FRAGMENT_DSIZE},
+};
+
+fn config() -> Config {
+    MockController::load_config(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/network.toml"
+    ))
+    .unwrap()
+}
+
+/// A core initialized with the example network, with the controller ends of its channels
+fn connect() -> (GuiCore, Receiver<GUICommands>, Sender<GUIEvents>) {
+    let (command_sender, commands) = unbounded();
+    let (events, event_receiver) = unbounded();
+    let mut core = GuiCore::new(command_sender, event_receiver);
+
+    let config = config();
+    events
+        .send(GUIEvents::Topology(
+            config.drone,
+            config.client,
+            config.server,
+        ))
+        .unwrap();
+    core.pump();
+    assert!(core.is_initialized());
+
+    (core, commands, events)
+}
+
+/// Pump the core until `done` holds, giving up after a few seconds
+fn pump_until(core: &mut GuiCore, done: impl Fn(&GuiCore) -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        core.pump();
+        if done(core) {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+#[test]
+fn a_confirmed_crash_removes_the_drone() {
+    let (mut core, commands, events) = connect();
+
+    core.crash(1).unwrap();
+    assert!(matches!(commands.try_recv(), Ok(GUICommands::Crash(1))));
+    assert_eq!(
+        core.state.journal.entries()[0].outcome,
+        CommandOutcome::Sent
+    );
+
+    events.send(GUIEvents::Crash(1)).unwrap();
+    core.drain();
+
+    assert!(!core.state.nodes.contains_key(&1));
+    assert!(core.state.pending.is_empty());
+    assert_eq!(
+        core.state.journal.entries()[0].outcome,
+        CommandOutcome::Confirmed
+    );
+    core.state.check_consistency().unwrap();
+}
+
+#[test]
+fn undo_sends_the_inverse_of_a_confirmed_edit() {
+    let (mut core, commands, events) = connect();
+
+    core.add_sender(20, 3).unwrap();
+    assert!(matches!(
+        commands.try_recv(),
+        Ok(GUICommands::AddSender(20, 3))
+    ));
+    events.send(GUIEvents::AddSender(20, 3)).unwrap();
+    core.drain();
+    assert!(core.state.graph.contains_edge(20, 3));
+
+    core.undo().unwrap();
+    assert!(matches!(
+        commands.try_recv(),
+        Ok(GUICommands::RemoveSender(20, 3))
+    ));
+    events.send(GUIEvents::RemoveSender(20, 3)).unwrap();
+    core.drain();
+    assert!(!core.state.graph.contains_edge(20, 3));
+
+    core.redo().unwrap();
+    assert!(matches!(
+        commands.try_recv(),
+        Ok(GUICommands::AddSender(20, 3))
+    ));
+}
+
+#[test]
+fn undo_skips_irreversible_edits_in_a_single_action() {
+    let (mut core, commands, _events) = connect();
+
+    core.add_sender(20, 3).unwrap();
+    core.crash(2).unwrap();
+    core.crash(4).unwrap();
+    while commands.try_recv().is_ok() {}
+
+    core.undo().unwrap();
+    assert!(matches!(
+        commands.try_recv(),
+        Ok(GUICommands::RemoveSender(20, 3))
+    ));
+    assert!(core.state.history.done().is_empty());
+    assert!(core.state.history.next_redo().is_some());
+
+    // With nothing left to revert the skipped edit is reported
+    core.crash(5).unwrap();
+    assert!(matches!(core.undo(), Err(GuiError::Irreversible(_))));
+    assert!(core.state.history.done().is_empty());
+}
+
+#[test]
+fn a_new_pdr_is_committed_once_sent_and_undone_to_the_previous_one() {
+    let (mut core, commands, _events) = connect();
+    let previous = core.state.nodes[&1].pdr;
+
+    core.set_pdr(1, 0.5).unwrap();
+    assert!(matches!(commands.try_recv(), Ok(GUICommands::SetPDR(1, _))));
+    assert!((core.state.nodes[&1].pdr - 0.5).abs() < f32::EPSILON);
+
+    core.undo().unwrap();
+    assert!(matches!(
+        commands.try_recv(),
+        Ok(GUICommands::SetPDR(1, pdr)) if (pdr - previous).abs() < f32::EPSILON
+    ));
+    assert!((core.state.nodes[&1].pdr - previous).abs() < f32::EPSILON);
+}
+
+#[test]
+fn drops_are_checked_against_the_last_pdr_sent() {
+    let (mut core, _commands, events) = connect();
+    let packet = Packet {
+        pack_type: PacketType::MsgFragment(Fragment {
+            fragment_index: 0,
+            total_n_fragments: 1,
+            length: 0,
+            data: [0; FRAGMENT_DSIZE],
+        }),
+        routing_header: SourceRoutingHeader {
+            hop_index: 1,
+            hops: vec![20, 1],
+        },
+        session_id: 0,
+    };
+    events
+        .send(GUIEvents::PacketDropped(1, packet.clone()))
+        .unwrap();
+    core.drain();
+    assert_eq!(core.state.pdr_checks.get(1).unwrap().samples(), 1);
+
+    // The drops at the old rate are forgotten once the new PDR is sent
+    core.set_pdr(1, 1.0).unwrap();
+    assert_eq!(core.state.pdr_checks.get(1).unwrap().samples(), 0);
+    core.state.pending.timeout = Duration::ZERO;
+    core.pump();
+
+    let samples = PDR_CHECK_MIN_SAMPLES * u64::from(PDR_CHECK_WINDOWS);
+    for _ in 0..samples {
+        events
+            .send(GUIEvents::PacketDropped(1, packet.clone()))
+            .unwrap();
+    }
+    core.drain();
+
+    let observation = core.state.pdr_checks.get(1).unwrap();
+    assert!((observation.configured - 1.0).abs() < f32::EPSILON);
+    assert_eq!(observation.samples(), samples);
+    assert_eq!(observation.verdict(), PdrVerdict::Consistent);
+}
+
+#[test]
+fn events_for_unknown_nodes_are_reported_without_changing_the_state() {
+    let (mut core, _commands, events) = connect();
+    let nodes = core.state.nodes.len();
+
+    events.send(GUIEvents::Crash(99)).unwrap();
+    events.send(GUIEvents::Spawn(40, vec![1, 99], 0.1)).unwrap();
+    core.drain();
+
+    assert_eq!(core.state.nodes.len(), nodes);
+    assert!(core
+        .state
+        .errors
+        .iter()
+        .all(|record| record.error == GuiError::UnknownNode(99)));
+    core.state.check_consistency().unwrap();
+}
+
+#[test]
+fn a_bulk_action_is_one_edit_and_reports_how_far_it_got() {
+    let (mut core, commands, _events) = connect();
+
+    core.state.selection.extend([1, 2]);
+    core.crash_selected().unwrap();
+    assert_eq!(commands.try_iter().count(), 2);
+    assert_eq!(core.state.history.done().len(), 1);
+
+    drop(commands);
+    let error = core.set_pdr_selected(0.5).unwrap_err();
+    assert!(matches!(
+        error,
+        GuiError::Incomplete {
+            done: 0,
+            total: 2,
+            ..
+        }
+    ));
+    assert_eq!(core.state.history.done().len(), 1);
+}
+
+#[test]
+fn a_layout_seed_set_at_start_up_places_the_first_layout() {
+    let place = || {
+        let (command_sender, _commands) = unbounded();
+        let (events, event_receiver) = unbounded();
+        let mut core = GuiCore::new(command_sender, event_receiver).with_layout_seed(42);
+        let config = config();
+        events
+            .send(GUIEvents::Topology(
+                config.drone,
+                config.client,
+                config.server,
+            ))
+            .unwrap();
+        core.pump();
+        let mut positions: Vec<_> = core
+            .state
+            .nodes
+            .values()
+            .map(|node| (node.id, node.x, node.y))
+            .collect();
+        positions.sort_by_key(|(id, _, _)| *id);
+        positions
+    };
+
+    assert_eq!(place(), place());
+}
+
+#[test]
+fn drives_the_mock_controller() {
+    let settings = MockSettings {
+        seed: Some(7),
+        flood_every: 0,
+        ..MockSettings::default()
+    };
+    let (sender, receiver, _controller) = MockController::spawn(config(), settings);
+    let mut core = GuiCore::new(sender, receiver);
+    assert!(pump_until(&mut core, GuiCore::is_initialized));
+
+    core.crash(1).unwrap();
+    core.add_sender(20, 3).unwrap();
+    assert!(pump_until(&mut core, |core| core.state.pending.is_empty()));
+
+    assert!(!core.state.nodes.contains_key(&1));
+    assert!(core.state.graph.contains_edge(20, 3));
+    core.state.check_consistency().unwrap();
+}